@@ -85,32 +85,26 @@ fn view<'a>(state: &State) -> Node<'a, Message, Layout> {
         .into();
     mouse_image_wrapper.push(image);
 
+    // name on the left, swatch on the right
+    let mut swatches = Node::new(Quad::new())
+        .grid([Length::Grow, Length::Fixed(20)], [Length::Shrink; 2])
+        .width(125)
+        .spacing(2);
+    for (name, color) in [
+        ("Primary", state.selected_primary_color),
+        ("Secondary", state.selected_secondary_color),
+    ] {
+        swatches.push(Node::new(Text::new(name.to_string()).with_font(font)));
+        swatches.push(Node::new(Quad::new().fill(color)).height(20).width(20));
+    }
+
     row![
         Node::spacer(),
         col![
             Node::spacer(),
             mouse_image_wrapper,
             Node::spacer(),
-            col![
-                row![
-                    Node::new(Text::new("Primary".to_string()).with_font(font)),
-                    Node::spacer(),
-                    Node::new(Quad::new().fill(state.selected_primary_color))
-                        .height(20)
-                        .width(20),
-                ]
-                .width(Length::Grow),
-                row![
-                    Node::new(Text::new("Secondary".to_string()).with_font(font)),
-                    Node::spacer(),
-                    Node::new(Quad::new().fill(state.selected_secondary_color))
-                        .height(20)
-                        .width(20),
-                ]
-                .width(Length::Grow),
-            ]
-            .width(125)
-            .spacing(2),
+            swatches,
             Node::spacer(),
             Node::spacer(),
         ],
@@ -1,6 +1,6 @@
 use icecube::button::Button;
 use icecube::font::{self};
-use icecube::layout::{Layout, Length};
+use icecube::layout::{Justify, Layout, Length};
 use icecube::palette::{BLUE_DARK, BLUE_LIGHT, MAIN_LIGHT};
 use icecube::quad::Quad;
 use icecube::text::Text;
@@ -32,24 +32,19 @@ fn view<'a>(state: &State) -> Node<'a, Message, Layout> {
         Node::new(Text::new(format!("{}", state.count)).with_font(font)).width(Length::Shrink);
     count.name = Some("counter value".to_string());
 
-    let count_row = row![Node::spacer(), count, Node::spacer()];
+    let count_row = row![count].justify(Justify::Center);
 
     let button_row = row![
-        Node::spacer(),
         make_button("+".into(), Message::Increment),
-        Node::spacer().width(2),
         make_button("-".into(), Message::Decrement),
-        Node::spacer(),
     ]
     .width(Length::Shrink)
+    .spacing(2)
     .padding(5);
 
-    row![
-        Node::spacer(),
-        col![Node::spacer(), count_row, button_row, Node::spacer()],
-        Node::spacer(),
-    ]
-    .height(Length::Grow)
+    row![col![count_row, button_row].justify(Justify::Center)]
+        .justify(Justify::Center)
+        .height(Length::Grow)
 }
 
 //TODO: Make an alias for LazyLock<FontType> and for Node
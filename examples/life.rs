@@ -2,24 +2,23 @@ use std::time::Duration;
 
 use icecube::button::Button;
 use icecube::image::Image;
+use icecube::key_binding::KeyBinding;
 use icecube::layout::{Layout, Length};
 use icecube::mouse_area::MouseArea;
 use icecube::palette::{BLUE_DARK, BLUE_LIGHT, MAIN_LIGHT};
 use icecube::quad::Quad;
 use icecube::text::Text;
 use icecube::tree::Node;
-use icecube::{col, font, row};
+use icecube::{col, font, row, KeyCode};
 
 use rand::prelude::*;
 
 /*
 WISHLIST
-- alpha value for images
 - default to shrink, not grow (for easier wrapper making)
 - bug?: border doesn't show up when you have a Quad wrapping an Image with shrink
     (adding padding fixes)
 - default frame or tick event with a delta saying how much time has passed
-- key events (do we have this yet?)
 - an event for button press that also includes the position
     (to click on individual cells)
 */
@@ -349,23 +348,35 @@ fn view<'a>(state: &State) -> Node<'a, Message, Layout> {
 
     wrapper.push(mouse_image_wrapper);
 
-    col![
-        Node::spacer(),
-        row![Node::spacer(), wrapper, Node::spacer()],
-        row![
+    let mut keys = Node::new(
+        KeyBinding::new()
+            .on_key(KeyCode::Space, Message::Pause)
+            .on_key(KeyCode::KeyS, Message::Step)
+            .on_key(KeyCode::KeyC, Message::Clear),
+    )
+    .width(Length::Grow)
+    .height(Length::Grow);
+
+    keys.push(
+        col![
             Node::spacer(),
-            step_button,
-            glider_button,
-            randomize_button,
-            clear_button,
+            row![Node::spacer(), wrapper, Node::spacer()],
+            row![
+                Node::spacer(),
+                step_button,
+                glider_button,
+                randomize_button,
+                clear_button,
+                Node::spacer(),
+            ]
+            .spacing(10),
+            row![Node::spacer(), pause_button, Node::spacer()],
             Node::spacer(),
         ]
-        .spacing(10),
-        row![Node::spacer(), pause_button, Node::spacer()],
-        Node::spacer(),
-    ]
-    .spacing(10)
-    .width(Length::Grow)
+        .spacing(10)
+        .width(Length::Grow),
+    );
+    keys
 }
 
 fn main() -> Result<(), pixels::Error> {
@@ -0,0 +1,68 @@
+use icecube::layout::{Layout, Length};
+use icecube::palette::{BLUE_DARK, MAIN_DARK, MAIN_LIGHT};
+use icecube::quad::Quad;
+use icecube::text::Text;
+use icecube::text_input::TextInput;
+use icecube::tree::Node;
+use icecube::{col, row};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Edit(String),
+    Submit,
+}
+
+#[derive(Default)]
+struct State {
+    name: String,
+    greeting: String,
+}
+
+fn update(m: Message, state: &mut State) {
+    match m {
+        Message::Edit(name) => state.name = name,
+        Message::Submit => state.greeting = format!("Hello, {}!", state.name),
+    }
+}
+
+fn view<'a>(state: &State) -> Node<'a, Message, Layout> {
+    let mut field = Node::new(
+        Quad::new()
+            .fill(MAIN_LIGHT)
+            .border_thickness(1)
+            .border_color(BLUE_DARK),
+    )
+    .width(Length::Grow)
+    .height(Length::Shrink)
+    .padding(2);
+    field.push(
+        Node::new(
+            TextInput::new(state.name.clone())
+                .with_color(MAIN_DARK)
+                .on_change(Message::Edit)
+                .on_submit(Message::Submit),
+        )
+        .width(Length::Grow),
+    );
+
+    row![
+        Node::spacer(),
+        col![
+            Node::spacer(),
+            Node::new(Text::new("Name:".to_string()).with_color(MAIN_DARK)),
+            field,
+            Node::new(Text::new(state.greeting.clone()).with_color(MAIN_DARK)),
+            Node::spacer(),
+        ]
+        .width(120)
+        .spacing(4),
+        Node::spacer(),
+    ]
+    .height(Length::Grow)
+}
+
+fn main() -> Result<(), pixels::Error> {
+    let initial_state = State::default();
+
+    icecube::run(initial_state, update, view, 320, 240, MAIN_LIGHT, |_| None)
+}
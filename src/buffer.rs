@@ -1,14 +1,168 @@
 use std::ops::{Index, IndexMut};
 
+use crate::{
+    layout::CalculatedLayout,
+    palette::{blend, Color},
+};
+
+/// An RGBA frame that elements draw into.
+///
+/// Drawing is limited to the rectangle on top of the clip stack, so an element can't paint over
+/// anything outside the region its parent was given.
+///
+/// Positions are in layout coordinates, which are shifted from the frame by the offset on top of
+/// the offset stack while the children of a scrolled container are drawn.
 pub struct Buffer<'a> {
     pub data: &'a mut [u8],
+    /// width in pixels
     pub width: usize,
+    /// height in pixels
+    pub height: usize,
+    /// distance in bytes between the starts of two consecutive rows
+    pub stride: usize,
+    /// in frame coordinates
+    clip_stack: Vec<CalculatedLayout>,
+    /// total offset of layout coordinates from frame coordinates
+    offset_stack: Vec<(u32, u32)>,
 }
 
 impl<'a> Buffer<'a> {
+    /// Wraps tightly packed RGBA rows of the given size.
+    pub fn new(data: &'a mut [u8], width: usize, height: usize) -> Self {
+        assert!(data.len() >= width * height * 4);
+        Self {
+            data,
+            width,
+            height,
+            stride: width * 4,
+            clip_stack: vec![],
+            offset_stack: vec![],
+        }
+    }
+
+    /// The rectangle drawing is currently limited to.
+    pub fn clip(&self) -> CalculatedLayout {
+        let (x, y) = self.offset();
+        match self.clip_stack.last() {
+            Some(clip) => CalculatedLayout {
+                x: clip.x + x,
+                y: clip.y + y,
+                ..*clip
+            },
+            None => CalculatedLayout {
+                x,
+                y,
+                w: self.width as u32,
+                h: self.height as u32,
+            },
+        }
+    }
+
+    /// Limits drawing to `region`, on top of whatever clip is already in place, until the matching
+    /// [`Buffer::pop_clip`].
+    pub fn push_clip(&mut self, region: CalculatedLayout) {
+        let (x, y) = self.offset();
+        let clip = self.clip().intersection(&region);
+        self.clip_stack.push(CalculatedLayout {
+            x: clip.x.saturating_sub(x),
+            y: clip.y.saturating_sub(y),
+            ..clip
+        });
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.clip_stack.pop();
+    }
+
+    /// How far layout coordinates currently are from frame coordinates.
+    pub fn offset(&self) -> (u32, u32) {
+        self.offset_stack.last().copied().unwrap_or_default()
+    }
+
+    /// Draws everything up to the matching [`Buffer::pop_offset`] `offset` pixels further up and
+    /// to the left.
+    pub fn push_offset(&mut self, offset: (u32, u32)) {
+        let (x, y) = self.offset();
+        self.offset_stack.push((x + offset.0, y + offset.1));
+    }
+
+    pub fn pop_offset(&mut self) {
+        self.offset_stack.pop();
+    }
+
     pub fn len(&self) -> usize {
         self.data.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Byte index of the pixel at `(x, y)`, or `None` if it is outside the frame.
+    pub fn pixel_index(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y * self.stride + x * 4)
+        } else {
+            None
+        }
+    }
+
+    /// Draws `color` over the pixel at `(x, y)`, blended by its alpha. Pixels outside the frame or
+    /// the current clip are ignored.
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
+        let (Ok(clip_x), Ok(clip_y)) = (u32::try_from(x), u32::try_from(y)) else {
+            return;
+        };
+        if !self.clip().contains((clip_x, clip_y)) {
+            return;
+        }
+        // anything inside the clip is at or past the offset
+        let (offset_x, offset_y) = self.offset();
+        if let Some(i) = self.pixel_index(x - offset_x as usize, y - offset_y as usize) {
+            self.blend_at(i, color);
+        }
+    }
+
+    /// Same as [`Buffer::set_pixel`], for callers that compute positions which may be negative.
+    pub fn set_pixel_signed(&mut self, x: i32, y: i32, color: Color) {
+        if let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) {
+            self.set_pixel(x, y, color);
+        }
+    }
+
+    /// Covers every other pixel of the frame with `color`, in a checkerboard, so whatever was
+    /// drawn shows through half as strongly.
+    pub fn dither(&mut self, color: Color) {
+        for y in 0..self.height {
+            for x in ((y % 2)..self.width).step_by(2) {
+                self.blend_at(y * self.stride + x * 4, color);
+            }
+        }
+    }
+
+    /// Draws `color` over the pixel starting at byte `i`.
+    fn blend_at(&mut self, i: usize, color: Color) {
+        let pixel = &mut self.data[i..(i + 4)];
+        match color[3] {
+            // most of what gets drawn is opaque
+            255 => pixel.copy_from_slice(&color),
+            0 => {}
+            _ => {
+                let below = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                pixel.copy_from_slice(&blend(below, color));
+            }
+        }
+    }
+
+    /// Fills every pixel of the frame with `color`.
+    pub fn clear(&mut self, color: Color) {
+        for y in 0..self.height {
+            let row = y * self.stride;
+            for pixel in self.data[row..(row + self.width * 4)].chunks_exact_mut(4) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+    }
 }
 
 //TODO: Consider implementing Index for range<usize>
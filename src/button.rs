@@ -1,4 +1,6 @@
-use crate::{element::Element, layout::CalculatedLayout, state_tree::StateNode, Input};
+use crate::{
+    buffer::Buffer, element::Element, layout::CalculatedLayout, state_tree::StateNode, Input,
+};
 
 // TODO make generic so that user can define Message
 pub struct Button<Message> {
@@ -36,7 +38,7 @@ impl<Message> Button<Message> {
 }
 
 impl<Message: Clone> Element<Message> for Button<Message> {
-    fn draw(&self, _frame: &mut [u8], _region: CalculatedLayout) {}
+    fn draw(&self, _frame: &mut Buffer, _tree: &StateNode, _region: CalculatedLayout) {}
     fn get_message(
         &mut self,
         _tree: &mut StateNode,
@@ -55,4 +57,8 @@ impl<Message: Clone> Element<Message> for Button<Message> {
         }
         return None;
     }
+
+    fn hit_test(&self, _tree: &StateNode, region: CalculatedLayout, pos: (u32, u32)) -> bool {
+        region.contains(pos)
+    }
 }
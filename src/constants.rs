@@ -1,2 +1,3 @@
-pub const WIDTH: u32 = 320; // TODO make this metadata for the frame buffer
 pub const SCALE: f64 = 3.0; // default pixel scaling factor / window size
+/// how many pixels one notch of a mouse wheel scrolls
+pub const WHEEL_LINE: u32 = 8;
@@ -1,10 +1,13 @@
+use std::time::Duration;
+
 use crate::{
+    buffer::Buffer,
     layout::{CalculatedLayout, Layout},
     state_tree::{self, StateNode},
 };
 
 pub trait Element<Message> {
-    fn draw(&self, frame: &mut [u8], region: CalculatedLayout);
+    fn draw(&self, frame: &mut Buffer, tree: &StateNode, region: CalculatedLayout);
     fn get_message(
         &mut self,
         tree: &mut StateNode,
@@ -27,6 +30,34 @@ pub trait Element<Message> {
     fn min_height(&self, width: u32) -> u32 {
         self.wrap(width).unwrap_or_default()
     }
+    /// Called once a frame with the time since the last one, whether or not there was any
+    /// input. For elements that change on their own, like animations.
+    fn tick(&mut self, _tree: &mut StateNode, _elapsed: Duration) -> Option<Message> {
+        None
+    }
+    /// Told the area this element's children cover once they have been positioned. Containers
+    /// that depend on the size of their content keep it from here.
+    fn children_positioned(&mut self, _content: CalculatedLayout) {}
+    /// How far this element's children are scrolled, in pixels to the right and down, or `None`
+    /// if it doesn't scroll. Children are drawn that much up and to the left of where layout put
+    /// them, and only see the mouse while it is inside this element.
+    fn scroll_offset(&self, _tree: &StateNode, _region: CalculatedLayout) -> Option<(u32, u32)> {
+        None
+    }
+    /// Whether this element takes the mouse at `pos`, hiding it from the siblings drawn behind.
+    /// Elements that handle the mouse or draw something solid there should say so.
+    fn hit_test(&self, _tree: &StateNode, _region: CalculatedLayout, _pos: (u32, u32)) -> bool {
+        false
+    }
+    /// Whether this element is taking keyboard input, like a text input being typed in. Focused
+    /// elements see the input before their ancestors do.
+    fn is_focused(&self, _tree: &StateNode) -> bool {
+        false
+    }
+    /// Should be `Tag::of` the type returned in [`Element::get_initial_state`].
+    fn tag(&self) -> state_tree::Tag {
+        state_tree::Tag::stateless()
+    }
     fn get_initial_state(&self) -> state_tree::State {
         state_tree::State::None
     }
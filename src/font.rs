@@ -1,9 +1,22 @@
-use std::{char, sync::LazyLock};
+use std::{char, collections::HashMap, path::Path, sync::LazyLock};
 
 use bdf2;
-use image::{ImageBuffer, Luma};
-
-use crate::{buffer::Buffer, palette::Color};
+use image::{
+    error::{ParameterError, ParameterErrorKind},
+    GrayImage, ImageError, ImageResult,
+};
+
+use crate::{
+    buffer::Buffer,
+    palette::{with_coverage, Color},
+};
+
+mod bmfont;
+mod error;
+mod psf;
+pub use bmfont::BmFont;
+pub use error::FontError;
+pub use psf::PsfFont;
 
 pub static OLDSCHOOL: LazyLock<FontType> =
     std::sync::LazyLock::new(|| FontType::Image(ImageFont::oldschool()));
@@ -21,36 +34,103 @@ pub static SCRAWL: LazyLock<FontType> =
 pub enum FontType {
     Image(ImageFont),
     Bdf(BdfFont),
+    BmFont(BmFont),
+    Psf(PsfFont),
 }
 
 pub struct BdfFont {
     font: bdf2::Font,
     space_width: usize,
     line_height: usize,
+    /// How far the baseline is below the top of the line
+    ascent: i32,
+    /// Widest glyph, from the font's bounding box
+    width: usize,
 }
 
 impl BdfFont {
     fn blackletter() -> Self {
-        Self {
-            //TODO: Consider adding a font feature flag to exclude this data from the binary
-            font: bdf2::read(
-                &include_bytes!("../src/resources/NotJam/Blackletter/NotJamBlkltr13-13.bdf")[..],
-            )
-            .unwrap(),
-            space_width: 8,
-            line_height: 14,
-        }
+        //TODO: Consider adding a font feature flag to exclude this data from the binary
+        Self::from_bytes(include_bytes!(
+            "../src/resources/NotJam/Blackletter/NotJamBlkltr13-13.bdf"
+        ))
+        .unwrap()
+        .line_height(14)
     }
     fn scrawl() -> Self {
+        Self::from_bytes(include_bytes!(
+            "../src/resources/NotJam/Scrawl/scrawl9-9.bdf"
+        ))
+        .unwrap()
+        .space_width(6)
+        .line_height(10)
+    }
+
+    /// Loads a BDF font file. The line height comes from the font's ascent and descent, falling
+    /// back to its bounding box, and the space width from how far its space glyph advances.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, bdf2::Error> {
+        Ok(bdf2::open(path)?.into())
+    }
+
+    /// Same as [`BdfFont::open`], for fonts already in memory, like ones from `include_bytes!`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bdf2::Error> {
+        Ok(bdf2::read(bytes)?.into())
+    }
+
+    /// Overrides the width of a space read from the font.
+    pub fn space_width(mut self, space_width: usize) -> Self {
+        self.space_width = space_width;
+        self
+    }
+
+    /// Overrides the line height read from the font.
+    pub fn line_height(mut self, line_height: usize) -> Self {
+        self.line_height = line_height;
+        self
+    }
+}
+
+impl From<bdf2::Font> for BdfFont {
+    fn from(font: bdf2::Font) -> Self {
+        let bounds = *font.bounds();
+        let property = |name: &str| match font.properties().get(name) {
+            Some(bdf2::Property::Integer(value)) => Some(*value),
+            _ => None,
+        };
+        let (ascent, line_height) = match (property("FONT_ASCENT"), property("FONT_DESCENT")) {
+            (Some(ascent), Some(descent)) => (ascent as i32, (ascent + descent).max(0) as usize),
+            _ => (bounds.height as i32 + bounds.y, bounds.height as usize),
+        };
+        let space_width = font
+            .glyphs()
+            .get(&' ')
+            .map(|space| match space.device_width() {
+                Some((advance, _)) => *advance,
+                None => space.width(),
+            })
+            // no space glyph, so guess at half the widest glyph
+            .unwrap_or(bounds.width / 2) as usize;
         Self {
-            font: bdf2::read(&include_bytes!("../src/resources/NotJam/Scrawl/scrawl9-9.bdf")[..])
-                .unwrap(),
-            space_width: 6,
-            line_height: 10,
+            font,
+            space_width,
+            line_height,
+            ascent,
+            width: bounds.width as usize,
         }
     }
 }
 
+impl BdfFont {
+    /// The glyph drawn for `character`, or the fallback character's when the font doesn't have
+    /// one
+    fn glyph(&self, character: char) -> Option<&bdf2::Glyph> {
+        let glyphs = self.font.glyphs();
+        glyphs
+            .get(&character)
+            .or_else(|| glyphs.get(&self.fallback_character()))
+    }
+}
+
 impl Font for BdfFont {
     fn draw_character(
         &self,
@@ -60,42 +140,44 @@ impl Font for BdfFont {
         character: char,
         color: Color,
     ) -> usize {
-        let glyph = self.font.glyphs().get(&character).unwrap();
-        let y_offset = glyph.bounds().y;
-        let glyph_height = glyph.bounds().height;
-
-        let baseline = screen_y as i32 + (self.height() as u32 - glyph_height) as i32;
+        if character == ' ' {
+            return self.space_width;
+        }
+        let Some(glyph) = self.glyph(character) else {
+            let width = replacement_width(self.height());
+            draw_replacement(buffer, screen_x, screen_y, width, self.height(), color);
+            return width;
+        };
+        let bounds = glyph.bounds();
+        // glyphs can reach above or below the line, and the clip discards whatever does
+        let top = screen_y as i32 + self.ascent - (bounds.height as i32 + bounds.y);
 
         glyph.pixels().for_each(|((x, y), value)| {
-            // let above_baseline = y - (glyph_height - self.height());
-            let frame_index = ((screen_x + x as usize)
-                + ((baseline - y_offset) as usize + y as usize) * buffer.width)
-                * 4;
-
-            if value && frame_index + 4 < buffer.len()
-            // our current workaround for out of
-            // bounds crashing
-            {
-                buffer.data[frame_index..(frame_index + 4)].copy_from_slice(&color);
+            if value {
+                buffer.set_pixel_signed((screen_x + x as usize) as i32, top + y as i32, color);
             }
         });
 
-        self.glyph_width(character)
+        glyph.width() as usize
     }
 
     fn glyph_width(&self, character: char) -> usize {
-        let glyph = self.font.glyphs().get(&character).unwrap();
-
         // special handling here, maybe just because our test bdf font is broken
         if character == ' ' {
-            self.space_width
-        } else {
-            glyph.width() as usize
+            return self.space_width;
+        }
+        match self.glyph(character) {
+            Some(glyph) => glyph.width() as usize,
+            None => replacement_width(self.height()),
         }
     }
 
+    fn has_glyph(&self, character: char) -> bool {
+        character == ' ' || self.font.glyphs().contains_key(&character)
+    }
+
     fn width(&self) -> usize {
-        14
+        self.width
     }
 
     fn height(&self) -> usize {
@@ -119,98 +201,267 @@ impl Font for FontType {
         match self {
             Self::Image(f) => f.draw_character(buffer, screen_x, screen_y, character, color),
             Self::Bdf(f) => f.draw_character(buffer, screen_x, screen_y, character, color),
+            Self::BmFont(f) => f.draw_character(buffer, screen_x, screen_y, character, color),
+            Self::Psf(f) => f.draw_character(buffer, screen_x, screen_y, character, color),
         }
     }
     fn glyph_width(&self, character: char) -> usize {
         match self {
             Self::Image(f) => f.glyph_width(character),
             Self::Bdf(f) => f.glyph_width(character),
+            Self::BmFont(f) => f.glyph_width(character),
+            Self::Psf(f) => f.glyph_width(character),
+        }
+    }
+    fn has_glyph(&self, character: char) -> bool {
+        match self {
+            Self::Image(f) => f.has_glyph(character),
+            Self::Bdf(f) => f.has_glyph(character),
+            Self::BmFont(f) => f.has_glyph(character),
+            Self::Psf(f) => f.has_glyph(character),
+        }
+    }
+    fn kerning(&self, left: char, right: char) -> i32 {
+        match self {
+            Self::Image(f) => f.kerning(left, right),
+            Self::Bdf(f) => f.kerning(left, right),
+            Self::BmFont(f) => f.kerning(left, right),
+            Self::Psf(f) => f.kerning(left, right),
         }
     }
     fn width(&self) -> usize {
         match self {
             Self::Image(f) => f.width(),
             Self::Bdf(f) => f.width(),
+            Self::BmFont(f) => f.width(),
+            Self::Psf(f) => f.width(),
         }
     }
     fn height(&self) -> usize {
         match self {
             Self::Image(f) => f.height(),
             Self::Bdf(f) => f.height(),
+            Self::BmFont(f) => f.height(),
+            Self::Psf(f) => f.height(),
         }
     }
     fn fallback_character(&self) -> char {
         match self {
             Self::Image(f) => f.fallback_character(),
             Self::Bdf(f) => f.fallback_character(),
+            Self::BmFont(f) => f.fallback_character(),
+            Self::Psf(f) => f.fallback_character(),
         }
     }
 }
 
+/// Where the glyphs of an [`ImageFont`] sit in its atlas. Glyphs are laid out in rows of equal
+/// cells, in character order.
+#[derive(Clone, Copy, Debug)]
+pub struct ImageFontMetrics {
+    /// The character in the first cell
+    pub first_char: char,
+    /// The character in the last cell. Anything past it, or before `first_char`, is drawn as
+    /// the fallback character.
+    pub last_char: char,
+    pub character_width: usize,
+    pub character_height: usize,
+    pub characters_per_row: usize,
+    /// Gap between neighbouring cells in the atlas
+    pub x_spacing: usize,
+    pub y_spacing: usize,
+    /// Gap before the first cell in the atlas
+    pub x_margin: usize,
+    pub y_margin: usize,
+}
+
+impl ImageFontMetrics {
+    fn cell_count(&self) -> usize {
+        (self.last_char as usize).saturating_sub(self.first_char as usize) + 1
+    }
+
+    /// Whether an atlas of `width` by `height` has room for every cell
+    fn fits(&self, width: u32, height: u32) -> bool {
+        let rows = self.cell_count().div_ceil(self.characters_per_row);
+        let columns = self.cell_count().min(self.characters_per_row);
+        let needed = |cells: usize, size: usize, spacing: usize, margin: usize| {
+            margin + cells * size + (cells - 1) * spacing
+        };
+        self.characters_per_row != 0
+            && needed(columns, self.character_width, self.x_spacing, self.x_margin)
+                <= width as usize
+            && needed(rows, self.character_height, self.y_spacing, self.y_margin) <= height as usize
+    }
+}
+
+/// A font drawn from a grid of glyphs in an image. The image's brightness is used as coverage,
+/// so white glyphs on black are drawn in the text color and grey pixels blend.
+///
+/// Every glyph is as wide as its cell unless [`ImageFont::proportional`] or
+/// [`ImageFont::glyph_widths`] say otherwise.
 pub struct ImageFont {
-    font_image: ImageBuffer<Luma<u8>, Vec<u8>>,
-    character_width: usize,
-    character_height: usize,
-    first_char: u8,
-    last_char: u8,
-    characters_per_row: usize,
-    x_image_spacing: usize,
-    y_image_spacing: usize,
-    y_image_margin: usize,
-    x_image_margin: usize,
+    font_image: GrayImage,
+    metrics: ImageFontMetrics,
+    /// The first column drawn from each cell, and how many are drawn
+    spans: Vec<(usize, usize)>,
+    space_width: usize,
+    kerning: HashMap<(char, char), i32>,
 }
 
 impl ImageFont {
     pub fn oldschool() -> Self {
-        let font = include_bytes!("resources/domsson_oldschool.png");
-        let font_image = image::load_from_memory(font)
-            .expect("failed to parse test font")
-            .into_luma8();
-
-        Self {
-            font_image,
-            first_char: b' ',
-            last_char: b'~',
+        let metrics = ImageFontMetrics {
+            first_char: ' ',
+            last_char: '~',
             character_width: 5,
             character_height: 7,
             characters_per_row: 18,
-            x_image_spacing: 2,
-            y_image_spacing: 2,
-            y_image_margin: 1,
-            x_image_margin: 1,
-        }
+            x_spacing: 2,
+            y_spacing: 2,
+            x_margin: 1,
+            y_margin: 1,
+        };
+        Self::from_bytes(include_bytes!("resources/domsson_oldschool.png"), metrics)
+            .expect("failed to parse test font")
     }
     pub fn mono_5_8() -> Self {
+        let metrics = ImageFontMetrics {
+            first_char: '!',
+            last_char: '~',
+            character_width: 4,
+            character_height: 8,
+            characters_per_row: ('~' as usize - '!' as usize + 1),
+            x_spacing: 2,
+            y_spacing: 1,
+            x_margin: 1,
+            y_margin: 1,
+        };
         // let font = include_bytes!("resources/debug.png");
-        let font = include_bytes!("resources/5x8mono.png");
-        let font_image = image::load_from_memory(font)
+        Self::from_bytes(include_bytes!("resources/5x8mono.png"), metrics)
             .expect("failed to parse test font")
-            .into_luma8();
+    }
 
+    /// Panics if the atlas is too small for the cells `metrics` describes.
+    pub fn new(font_image: GrayImage, metrics: ImageFontMetrics) -> Self {
+        assert!(
+            metrics.fits(font_image.width(), font_image.height()),
+            "font atlas is too small for its glyphs"
+        );
         Self {
             font_image,
-            first_char: b'!',
-            last_char: b'~',
-            character_width: 4,
-            character_height: 8,
-            characters_per_row: (b'~' - b'!' + 1) as usize,
-            x_image_spacing: 2,
-            y_image_spacing: 1,
-            y_image_margin: 1,
-            x_image_margin: 1,
+            spans: vec![(0, metrics.character_width); metrics.cell_count()],
+            space_width: metrics.character_width,
+            kerning: HashMap::new(),
+            metrics,
+        }
+    }
+
+    /// Loads a font atlas in any format the `image` crate can read, failing if it is too small
+    /// for the cells `metrics` describes.
+    pub fn open(path: impl AsRef<Path>, metrics: ImageFontMetrics) -> ImageResult<Self> {
+        Self::checked(image::open(path)?.into_luma8(), metrics)
+    }
+
+    /// Same as [`ImageFont::open`], for atlases already in memory, like ones from
+    /// `include_bytes!`.
+    pub fn from_bytes(bytes: &[u8], metrics: ImageFontMetrics) -> ImageResult<Self> {
+        Self::checked(image::load_from_memory(bytes)?.into_luma8(), metrics)
+    }
+
+    fn checked(font_image: GrayImage, metrics: ImageFontMetrics) -> ImageResult<Self> {
+        match metrics.fits(font_image.width(), font_image.height()) {
+            true => Ok(Self::new(font_image, metrics)),
+            false => Err(ImageError::Parameter(ParameterError::from_kind(
+                ParameterErrorKind::DimensionMismatch,
+            ))),
+        }
+    }
+
+    pub fn metrics(&self) -> &ImageFontMetrics {
+        &self.metrics
+    }
+
+    /// Narrows every glyph to the columns of its cell that have something in them, for
+    /// proportional fonts packed into equal cells. Empty cells keep their full width.
+    pub fn proportional(mut self) -> Self {
+        let (width, height) = (self.metrics.character_width, self.metrics.character_height);
+        for (index, span) in self.spans.iter_mut().enumerate() {
+            let (x, y) = Self::cell_origin(&self.metrics, index);
+            let lit = |i: &usize| {
+                (0..height).any(|j| self.font_image[((x + i) as u32, (y + j) as u32)].0[0] > 0)
+            };
+            if let (Some(first), Some(last)) = ((0..width).find(lit), (0..width).rfind(lit)) {
+                *span = (first, last + 1 - first);
+            }
+        }
+        self
+    }
+
+    /// Sets the widths of individual glyphs, like ones read from a table shipped alongside the
+    /// atlas. Each is drawn from the left edge of its cell. A width for `' '` sets how far a
+    /// space advances.
+    pub fn glyph_widths(mut self, widths: impl IntoIterator<Item = (char, usize)>) -> Self {
+        for (character, width) in widths {
+            let width = width.min(self.metrics.character_width);
+            match self.cell(character) {
+                _ if character == ' ' => self.space_width = width,
+                Some(index) => self.spans[index] = (0, width),
+                None => {}
+            }
         }
+        self
+    }
+
+    /// Moves the second character of each pair closer (negative) or further away when it
+    /// follows the first.
+    pub fn kerning_pairs(mut self, pairs: impl IntoIterator<Item = ((char, char), i32)>) -> Self {
+        self.kerning.extend(pairs);
+        self
+    }
+
+    fn cell(&self, character: char) -> Option<usize> {
+        let m = &self.metrics;
+        (m.first_char..=m.last_char)
+            .contains(&character)
+            .then(|| character as usize - m.first_char as usize)
+    }
+
+    /// The cell drawn for `character`, or the fallback character's when the font doesn't have
+    /// one
+    fn glyph_cell(&self, character: char) -> Option<usize> {
+        self.cell(character)
+            .or_else(|| self.cell(self.fallback_character()))
+    }
+
+    /// Top left corner of a cell in the atlas
+    fn cell_origin(m: &ImageFontMetrics, index: usize) -> (usize, usize) {
+        let (x, y) = (index % m.characters_per_row, index / m.characters_per_row);
+        (
+            m.x_margin + x * (m.character_width + m.x_spacing),
+            m.y_margin + y * (m.character_height + m.y_spacing),
+        )
     }
 }
 
 impl Font for ImageFont {
-    fn glyph_width(&self, _: char) -> usize {
-        self.character_width
+    fn glyph_width(&self, character: char) -> usize {
+        match self.glyph_cell(character) {
+            _ if character == ' ' => self.space_width,
+            Some(index) => self.spans[index].1,
+            None => self.metrics.character_width,
+        }
+    }
+    fn has_glyph(&self, character: char) -> bool {
+        character == ' ' || self.cell(character).is_some()
+    }
+    fn kerning(&self, left: char, right: char) -> i32 {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0)
     }
     fn width(&self) -> usize {
-        self.character_width
+        self.metrics.character_width
     }
     fn height(&self) -> usize {
-        self.character_height
+        self.metrics.character_height
     }
     fn fallback_character(&self) -> char {
         '?'
@@ -224,39 +475,34 @@ impl Font for ImageFont {
         character: char,
         color: Color,
     ) -> usize {
-        let index = match character {
-            ' ' => return self.character_width,
-            c if self.first_char <= c as u8 && self.last_char >= c as u8 => c,
-            _ => self.fallback_character(),
-        } as u8
-            - self.first_char;
-
-        let x = index as usize % self.characters_per_row;
-        let y = index as usize / self.characters_per_row;
-
-        let (img_x, img_y) = (
-            (x * (self.width() + self.x_image_spacing)),
-            (y * (self.height() + self.y_image_spacing)),
-        );
-
-        for j in 0..self.height() {
-            for i in 0..self.width() {
-                let frame_index = ((screen_x + i) + (screen_y + j) * buffer.width) * 4;
-
-                let font_pixel = self.font_image[(
-                    img_x as u32 + i as u32 + self.x_image_margin as u32,
-                    img_y as u32 + j as u32 + self.y_image_margin as u32,
-                )];
-
-                if font_pixel.0[0] > 0 && frame_index + 4 < buffer.len()
-                // our current workaround for out of
-                // bounds crashing
-                {
-                    buffer.data[frame_index..(frame_index + 4)].copy_from_slice(&color);
+        let m = &self.metrics;
+        let index = match self.glyph_cell(character) {
+            _ if character == ' ' => return self.space_width,
+            Some(index) => index,
+            None => {
+                let (width, height) = (m.character_width, m.character_height);
+                draw_replacement(buffer, screen_x, screen_y, width, height, color);
+                return width;
+            }
+        };
+        let (img_x, img_y) = Self::cell_origin(m, index);
+        let (first, width) = self.spans[index];
+
+        for j in 0..m.character_height {
+            for i in 0..width {
+                let font_pixel = self.font_image[((img_x + first + i) as u32, (img_y + j) as u32)];
+
+                // grey pixels in the font image are partly covered, for anti-aliased fonts
+                if font_pixel.0[0] > 0 {
+                    buffer.set_pixel(
+                        screen_x + i,
+                        screen_y + j,
+                        with_coverage(color, font_pixel.0[0]),
+                    );
                 }
             }
         }
-        self.character_width
+        width
     }
 }
 
@@ -271,7 +517,112 @@ pub trait Font {
     ) -> usize;
 
     fn glyph_width(&self, character: char) -> usize;
+    /// Whether the font has its own glyph for `character`, rather than drawing a stand-in
+    fn has_glyph(&self, character: char) -> bool;
+    /// How much closer (negative) or further apart `right` is drawn when it follows `left`
+    fn kerning(&self, _left: char, _right: char) -> i32 {
+        0
+    }
     fn width(&self) -> usize;
     fn height(&self) -> usize;
+    /// Drawn in place of characters the font doesn't have. When the font lacks this one too, an
+    /// empty box is drawn instead.
     fn fallback_character(&self) -> char;
 }
+
+/// A primary font followed by fallbacks. Each character is drawn with the first font that has
+/// it. Characters none of them have are drawn as the first fallback character any of them has,
+/// and as the primary font's replacement box when that fails too.
+///
+/// Line height and width come from the primary font, and every glyph is drawn from the top of
+/// the line.
+#[derive(Clone, Copy)]
+pub struct FontStack<'a> {
+    pub primary: &'a FontType,
+    pub fallbacks: &'a [&'a FontType],
+}
+
+impl<'a> FontStack<'a> {
+    pub fn new(primary: &'a FontType, fallbacks: &'a [&'a FontType]) -> Self {
+        Self { primary, fallbacks }
+    }
+
+    fn fonts(&self) -> impl Iterator<Item = &'a FontType> {
+        std::iter::once(self.primary).chain(self.fallbacks.iter().copied())
+    }
+
+    /// The font to draw `character` with, and the character it should draw
+    fn resolve(&self, character: char) -> (&'a FontType, char) {
+        let replacement = self.primary.fallback_character();
+        self.fonts()
+            .find(|font| font.has_glyph(character))
+            .map(|font| (font, character))
+            .or_else(|| {
+                self.fonts()
+                    .find(|font| font.has_glyph(replacement))
+                    .map(|font| (font, replacement))
+            })
+            .unwrap_or((self.primary, character))
+    }
+}
+
+impl Font for FontStack<'_> {
+    fn draw_character(
+        &self,
+        buffer: &mut Buffer,
+        screen_x: usize,
+        screen_y: usize,
+        character: char,
+        color: Color,
+    ) -> usize {
+        let (font, character) = self.resolve(character);
+        font.draw_character(buffer, screen_x, screen_y, character, color)
+    }
+    fn glyph_width(&self, character: char) -> usize {
+        let (font, character) = self.resolve(character);
+        font.glyph_width(character)
+    }
+    fn has_glyph(&self, character: char) -> bool {
+        self.fonts().any(|font| font.has_glyph(character))
+    }
+    /// Only pairs drawn with the same font are kerned
+    fn kerning(&self, left: char, right: char) -> i32 {
+        let ((left_font, left), (right_font, right)) = (self.resolve(left), self.resolve(right));
+        match std::ptr::eq(left_font, right_font) {
+            true => left_font.kerning(left, right),
+            false => 0,
+        }
+    }
+    fn width(&self) -> usize {
+        self.primary.width()
+    }
+    fn height(&self) -> usize {
+        self.primary.height()
+    }
+    fn fallback_character(&self) -> char {
+        self.primary.fallback_character()
+    }
+}
+
+/// Width of the box drawn for a missing glyph in a font `height` pixels tall
+fn replacement_width(height: usize) -> usize {
+    (height / 2).max(3)
+}
+
+/// Outlines a `width` by `height` box, the last resort for characters no font has
+fn draw_replacement(
+    buffer: &mut Buffer,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    color: Color,
+) {
+    for j in 0..height {
+        for i in 0..width {
+            if i == 0 || j == 0 || i + 1 == width || j + 1 == height {
+                buffer.set_pixel(x + i, y + j, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use std::{collections::HashMap, path::Path};
+
+use image::{DynamicImage, GrayImage};
+
+use super::{draw_replacement, replacement_width, Font, FontError};
+use crate::{
+    buffer::Buffer,
+    palette::{with_coverage, Color},
+};
+
+/// Where a glyph sits on its page, and how it sits on the line
+struct Glyph {
+    page: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    /// From the pen position to the glyph's top left corner
+    x_offset: i32,
+    y_offset: i32,
+    /// How far the pen moves on after the glyph
+    advance: i32,
+}
+
+/// What a `.fnt` file describes, before its pages are loaded
+#[derive(Default)]
+struct Descriptor {
+    line_height: u32,
+    /// Page image file names, by page id
+    pages: Vec<String>,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), i32>,
+}
+
+/// A font in AngelCode's BMFont format: a `.fnt` descriptor, in its text or binary flavour, and
+/// the page images its glyphs are packed into.
+///
+/// Pages are read as coverage, their brightness times their alpha, so both white glyphs on a
+/// transparent page and white on black draw in the text color.
+pub struct BmFont {
+    pages: Vec<GrayImage>,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), i32>,
+    line_height: usize,
+    /// Furthest any glyph advances
+    width: usize,
+}
+
+impl BmFont {
+    /// Loads a `.fnt` file along with its pages, which are looked up next to it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FontError> {
+        let path = path.as_ref();
+        let descriptor = Descriptor::parse(&std::fs::read(path)?)?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let pages = descriptor
+            .pages
+            .iter()
+            .map(|file| image::open(directory.join(file)))
+            .collect::<Result<_, _>>()?;
+        Self::new(descriptor, pages)
+    }
+
+    /// Same as [`BmFont::open`], for fonts already in memory. `pages` are the encoded page
+    /// images in page id order; the file names in the descriptor are ignored.
+    pub fn from_bytes(descriptor: &[u8], pages: &[&[u8]]) -> Result<Self, FontError> {
+        let descriptor = Descriptor::parse(descriptor)?;
+        let pages = pages
+            .iter()
+            .map(|bytes| image::load_from_memory(bytes))
+            .collect::<Result<_, _>>()?;
+        Self::new(descriptor, pages)
+    }
+
+    fn new(descriptor: Descriptor, pages: Vec<DynamicImage>) -> Result<Self, FontError> {
+        let pages: Vec<_> = pages.into_iter().map(coverage).collect();
+        let on_its_page = |glyph: &Glyph| {
+            pages.get(glyph.page).is_some_and(|page| {
+                let fits = |start: u32, size: u32, page_size: u32| {
+                    start.checked_add(size).is_some_and(|end| end <= page_size)
+                };
+                fits(glyph.x, glyph.width, page.width())
+                    && fits(glyph.y, glyph.height, page.height())
+            })
+        };
+        if !descriptor.glyphs.values().all(on_its_page) {
+            return Err(FontError::malformed("glyph lies outside its page"));
+        }
+        let width = descriptor
+            .glyphs
+            .values()
+            .map(|glyph| glyph.advance.max(0) as usize)
+            .max()
+            .unwrap_or_default();
+        Ok(Self {
+            pages,
+            glyphs: descriptor.glyphs,
+            kerning: descriptor.kerning,
+            line_height: descriptor.line_height as usize,
+            width,
+        })
+    }
+
+    /// The glyph drawn for `character`, or the fallback character's when the font doesn't have
+    /// one
+    fn glyph(&self, character: char) -> Option<&Glyph> {
+        self.glyphs
+            .get(&character)
+            .or_else(|| self.glyphs.get(&self.fallback_character()))
+    }
+}
+
+impl Font for BmFont {
+    fn draw_character(
+        &self,
+        buffer: &mut Buffer,
+        screen_x: usize,
+        screen_y: usize,
+        character: char,
+        color: Color,
+    ) -> usize {
+        let Some(glyph) = self.glyph(character) else {
+            let width = replacement_width(self.line_height);
+            draw_replacement(buffer, screen_x, screen_y, width, self.line_height, color);
+            return width;
+        };
+        let page = &self.pages[glyph.page];
+        for j in 0..glyph.height {
+            for i in 0..glyph.width {
+                let coverage = page[(glyph.x + i, glyph.y + j)].0[0];
+                if coverage > 0 {
+                    buffer.set_pixel_signed(
+                        screen_x as i32 + glyph.x_offset + i as i32,
+                        screen_y as i32 + glyph.y_offset + j as i32,
+                        with_coverage(color, coverage),
+                    );
+                }
+            }
+        }
+        glyph.advance.max(0) as usize
+    }
+
+    fn glyph_width(&self, character: char) -> usize {
+        match self.glyph(character) {
+            Some(glyph) => glyph.advance.max(0) as usize,
+            None => replacement_width(self.line_height),
+        }
+    }
+
+    fn has_glyph(&self, character: char) -> bool {
+        self.glyphs.contains_key(&character)
+    }
+
+    fn kerning(&self, left: char, right: char) -> i32 {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0)
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.line_height
+    }
+
+    fn fallback_character(&self) -> char {
+        '?'
+    }
+}
+
+/// Brightness times alpha for every pixel of a page
+fn coverage(page: DynamicImage) -> GrayImage {
+    let page = page.into_luma_alpha8();
+    GrayImage::from_fn(page.width(), page.height(), |x, y| {
+        let [luma, alpha] = page[(x, y)].0;
+        [(luma as u16 * alpha as u16 / 255) as u8].into()
+    })
+}
+
+impl Descriptor {
+    fn parse(bytes: &[u8]) -> Result<Self, FontError> {
+        match bytes.strip_prefix(b"BMF") {
+            Some(binary) => Self::parse_binary(binary),
+            None => match std::str::from_utf8(bytes) {
+                Ok(text) => Self::parse_text(text),
+                Err(_) => Err(FontError::malformed(
+                    "not a text or binary BMFont descriptor",
+                )),
+            },
+        }
+    }
+
+    /// Lines of a tag followed by `key=value` attributes, like
+    /// `char id=65 x=0 y=0 width=5 height=7 xoffset=0 yoffset=2 xadvance=6 page=0 chnl=15`
+    fn parse_text(text: &str) -> Result<Self, FontError> {
+        let mut descriptor = Self::default();
+        for line in text.lines() {
+            let line = line.trim();
+            let (tag, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let attributes = attributes(rest);
+            let number = |key: &str| -> Result<i64, FontError> {
+                let value = attributes
+                    .get(key)
+                    .ok_or_else(|| FontError::malformed(format!("`{tag}` is missing `{key}`")))?;
+                value
+                    .parse()
+                    .map_err(|_| FontError::malformed(format!("`{key}={value}` isn't a number")))
+            };
+            let unsigned = |key: &str| -> Result<u32, FontError> {
+                let value = number(key)?;
+                u32::try_from(value)
+                    .map_err(|_| FontError::malformed(format!("`{key}={value}` is out of range")))
+            };
+            match tag {
+                "common" => descriptor.line_height = unsigned("lineHeight")?,
+                "page" => {
+                    let id = unsigned("id")? as usize;
+                    let file = attributes
+                        .get("file")
+                        .ok_or_else(|| FontError::malformed("`page` is missing `file`"))?;
+                    if descriptor.pages.len() <= id {
+                        descriptor.pages.resize(id + 1, String::new());
+                    }
+                    descriptor.pages[id] = file.to_string();
+                }
+                "char" => {
+                    // some tools write an id of -1 for their stand-in glyph
+                    let Some(character) = to_char(number("id")?) else {
+                        continue;
+                    };
+                    let glyph = Glyph {
+                        page: unsigned("page")? as usize,
+                        x: unsigned("x")?,
+                        y: unsigned("y")?,
+                        width: unsigned("width")?,
+                        height: unsigned("height")?,
+                        x_offset: number("xoffset")? as i32,
+                        y_offset: number("yoffset")? as i32,
+                        advance: number("xadvance")? as i32,
+                    };
+                    descriptor.glyphs.insert(character, glyph);
+                }
+                "kerning" => {
+                    if let (Some(first), Some(second)) =
+                        (to_char(number("first")?), to_char(number("second")?))
+                    {
+                        let amount = number("amount")? as i32;
+                        descriptor.kerning.insert((first, second), amount);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(descriptor)
+    }
+
+    /// A version byte, then blocks of a type byte, a little-endian `u32` length and the block
+    fn parse_binary(bytes: &[u8]) -> Result<Self, FontError> {
+        let truncated = || FontError::malformed("binary descriptor ends early");
+        let (&version, mut rest) = bytes.split_first().ok_or_else(truncated)?;
+        if version != 3 {
+            return Err(FontError::malformed(format!(
+                "unsupported binary BMFont version {version}"
+            )));
+        }
+
+        let mut descriptor = Self::default();
+        while let Some((&kind, after)) = rest.split_first() {
+            let length = after.get(..4).ok_or_else(truncated)?;
+            let length = u32::from_le_bytes(length.try_into().unwrap()) as usize;
+            let block = after.get(4..(4 + length)).ok_or_else(truncated)?;
+            rest = &after[(4 + length)..];
+            match kind {
+                // common
+                2 if block.len() >= 2 => descriptor.line_height = u16_at(block, 0) as u32,
+                2 => return Err(truncated()),
+                // pages, as null-terminated file names
+                3 => {
+                    descriptor.pages = block
+                        .split(|&b| b == 0)
+                        .filter(|name| !name.is_empty())
+                        .map(|name| String::from_utf8_lossy(name).into_owned())
+                        .collect()
+                }
+                // chars
+                4 => {
+                    for c in block.chunks_exact(20) {
+                        let Some(character) = to_char(u32_at(c, 0) as i64) else {
+                            continue;
+                        };
+                        let glyph = Glyph {
+                            x: u16_at(c, 4) as u32,
+                            y: u16_at(c, 6) as u32,
+                            width: u16_at(c, 8) as u32,
+                            height: u16_at(c, 10) as u32,
+                            x_offset: u16_at(c, 12) as i16 as i32,
+                            y_offset: u16_at(c, 14) as i16 as i32,
+                            advance: u16_at(c, 16) as i16 as i32,
+                            page: c[18] as usize,
+                        };
+                        descriptor.glyphs.insert(character, glyph);
+                    }
+                }
+                // kerning pairs
+                5 => {
+                    for pair in block.chunks_exact(10) {
+                        let first = to_char(u32_at(pair, 0) as i64);
+                        let second = to_char(u32_at(pair, 4) as i64);
+                        if let (Some(first), Some(second)) = (first, second) {
+                            let amount = u16_at(pair, 8) as i16 as i32;
+                            descriptor.kerning.insert((first, second), amount);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(descriptor)
+    }
+}
+
+/// Splits `key=value key="quoted value"` into its pairs
+fn attributes(rest: &str) -> HashMap<&str, &str> {
+    let mut attributes = HashMap::new();
+    let mut rest = rest.trim_start();
+    while let Some((key, after)) = rest.split_once('=') {
+        let (value, remaining) = match after.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after.split_once(char::is_whitespace).unwrap_or((after, "")),
+        };
+        attributes.insert(key.trim(), value);
+        rest = remaining.trim_start();
+    }
+    attributes
+}
+
+fn to_char(id: i64) -> Option<char> {
+    u32::try_from(id).ok().and_then(char::from_u32)
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..(at + 4)].try_into().unwrap())
+}
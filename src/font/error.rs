@@ -0,0 +1,51 @@
+use std::{fmt, io};
+
+use image::ImageError;
+
+/// Why a [`BmFont`](super::BmFont) or [`PsfFont`](super::PsfFont) couldn't be loaded.
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    /// One of a BMFont's page images couldn't be read
+    Image(ImageError),
+    /// The data isn't laid out the way the format says it should be
+    Malformed(String),
+}
+
+impl FontError {
+    pub(super) fn malformed(reason: impl Into<String>) -> Self {
+        FontError::Malformed(reason.into())
+    }
+}
+
+impl From<io::Error> for FontError {
+    fn from(value: io::Error) -> Self {
+        FontError::Io(value)
+    }
+}
+
+impl From<ImageError> for FontError {
+    fn from(value: ImageError) -> Self {
+        FontError::Image(value)
+    }
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "couldn't read font: {error}"),
+            FontError::Image(error) => write!(f, "couldn't read font page: {error}"),
+            FontError::Malformed(reason) => write!(f, "malformed font: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FontError::Io(error) => Some(error),
+            FontError::Image(error) => Some(error),
+            FontError::Malformed(_) => None,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::{collections::HashMap, path::Path};
+
+use super::{draw_replacement, Font, FontError};
+use crate::{buffer::Buffer, palette::Color};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+
+/// A Linux console font in the PSF1 or PSF2 format, with one bit per pixel and every glyph the
+/// same size.
+///
+/// Fonts with a unicode table map characters through it. Ones without draw each character with
+/// the glyph at its code point.
+pub struct PsfFont {
+    width: usize,
+    height: usize,
+    /// Bytes in each row of a glyph's bitmap
+    row_bytes: usize,
+    /// Bytes in each glyph's bitmap, which can have padding after its rows
+    glyph_bytes: usize,
+    glyph_count: usize,
+    bitmaps: Vec<u8>,
+    unicode: Option<HashMap<char, usize>>,
+}
+
+impl PsfFont {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FontError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Same as [`PsfFont::open`], for fonts already in memory, like ones from `include_bytes!`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FontError> {
+        if let Some(rest) = bytes.strip_prefix(&PSF1_MAGIC) {
+            Self::psf1(rest)
+        } else if bytes.starts_with(&PSF2_MAGIC) {
+            Self::psf2(bytes)
+        } else {
+            Err(FontError::malformed("not a PSF1 or PSF2 font"))
+        }
+    }
+
+    /// A mode byte and the glyph height, then 256 or 512 glyphs 8 pixels wide, then an optional
+    /// unicode table of little-endian `u16`s
+    fn psf1(bytes: &[u8]) -> Result<Self, FontError> {
+        let [mode, height, ref data @ ..] = *bytes else {
+            return Err(truncated());
+        };
+        let glyph_count = if mode & 0x01 != 0 { 512 } else { 256 };
+        let glyph_bytes = height as usize;
+        let bitmaps = data
+            .get(..(glyph_count * glyph_bytes))
+            .ok_or_else(truncated)?;
+
+        let unicode = (mode & 0x06 != 0).then(|| {
+            let entries = data[bitmaps.len()..]
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]));
+            // 0xFFFF ends each glyph's entry, and 0xFFFE starts the sequences of combining
+            // characters at the end of it, which are skipped
+            let mut unicode = HashMap::new();
+            let (mut glyph, mut in_sequences) = (0, false);
+            for entry in entries {
+                match entry {
+                    0xFFFF => (glyph, in_sequences) = (glyph + 1, false),
+                    0xFFFE => in_sequences = true,
+                    _ if in_sequences => {}
+                    code => {
+                        if let Some(character) = char::from_u32(code as u32) {
+                            unicode.entry(character).or_insert(glyph);
+                        }
+                    }
+                }
+            }
+            unicode
+        });
+
+        Ok(Self {
+            width: 8,
+            height: height as usize,
+            row_bytes: 1,
+            glyph_bytes,
+            glyph_count,
+            bitmaps: bitmaps.to_vec(),
+            unicode,
+        })
+    }
+
+    /// A header of little-endian `u32`s, then the glyphs, then an optional unicode table of
+    /// UTF-8
+    fn psf2(bytes: &[u8]) -> Result<Self, FontError> {
+        let field = |i: usize| {
+            bytes
+                .get((i * 4)..(i * 4 + 4))
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+                .ok_or_else(truncated)
+        };
+        let (header_size, flags, glyph_count) = (field(2)?, field(3)?, field(4)?);
+        let (glyph_bytes, height, width) = (field(5)?, field(6)?, field(7)?);
+        let row_bytes = width.div_ceil(8);
+        if glyph_bytes < row_bytes * height {
+            return Err(FontError::malformed("glyphs are smaller than their size"));
+        }
+        let end = glyph_count
+            .checked_mul(glyph_bytes)
+            .and_then(|length| length.checked_add(header_size))
+            .ok_or_else(truncated)?;
+        let bitmaps = bytes.get(header_size..end).ok_or_else(truncated)?;
+
+        let unicode = (flags & 0x01 != 0).then(|| {
+            // 0xFF ends each glyph's entry, and 0xFE starts the sequences of combining characters
+            // at the end of it, which are skipped
+            let mut unicode = HashMap::new();
+            for (glyph, entry) in bytes[end..].split(|&b| b == 0xFF).enumerate() {
+                let singles = entry.split(|&b| b == 0xFE).next().unwrap_or_default();
+                for character in String::from_utf8_lossy(singles).chars() {
+                    unicode.entry(character).or_insert(glyph);
+                }
+            }
+            unicode
+        });
+
+        Ok(Self {
+            width,
+            height,
+            row_bytes,
+            glyph_bytes,
+            glyph_count,
+            bitmaps: bitmaps.to_vec(),
+            unicode,
+        })
+    }
+
+    fn index(&self, character: char) -> Option<usize> {
+        let index = match &self.unicode {
+            Some(unicode) => *unicode.get(&character)?,
+            None => character as usize,
+        };
+        (index < self.glyph_count).then_some(index)
+    }
+}
+
+fn truncated() -> FontError {
+    FontError::malformed("font data ends early")
+}
+
+impl Font for PsfFont {
+    fn draw_character(
+        &self,
+        buffer: &mut Buffer,
+        screen_x: usize,
+        screen_y: usize,
+        character: char,
+        color: Color,
+    ) -> usize {
+        let Some(index) = self
+            .index(character)
+            .or_else(|| self.index(self.fallback_character()))
+        else {
+            draw_replacement(buffer, screen_x, screen_y, self.width, self.height, color);
+            return self.width;
+        };
+        let bitmap = &self.bitmaps[(index * self.glyph_bytes)..];
+        for j in 0..self.height {
+            for i in 0..self.width {
+                // the leftmost pixel is the highest bit
+                if bitmap[j * self.row_bytes + i / 8] & (0x80 >> (i % 8)) != 0 {
+                    buffer.set_pixel(screen_x + i, screen_y + j, color);
+                }
+            }
+        }
+        self.width
+    }
+
+    fn glyph_width(&self, _: char) -> usize {
+        self.width
+    }
+
+    fn has_glyph(&self, character: char) -> bool {
+        self.index(character).is_some()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn fallback_character(&self) -> char {
+        '?'
+    }
+}
@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use image::RgbaImage;
+
+use crate::{layout::LayoutError, modal::Layers, palette::Color, runtime::Runtime, Input};
+
+/// Runs an app without a window, drawing into an owned RGBA buffer.
+///
+/// Takes the same `update`/`view`/`timer` closures as [`crate::run`], but instead of an event
+/// loop the caller feeds it frames of [`Input`] along with how much time each frame took. Useful
+/// for tests, CI and rendering thumbnails on machines without a display.
+pub struct Headless<'a, State, Message, Update, View, Timer> {
+    runtime: Runtime<'a, State, Message, Update, View, Timer>,
+    frame: Vec<u8>,
+    width: u32,
+    height: u32,
+    prev_mouse_pos: Option<(u32, u32)>,
+}
+
+impl<'a, State, Message, Update, View, Screen, Timer>
+    Headless<'a, State, Message, Update, View, Timer>
+where
+    Update: Fn(Message, &mut State),
+    View: Fn(&State) -> Screen,
+    Screen: Into<Layers<'a, Message>>,
+    Timer: Fn(Duration) -> Option<Message>,
+{
+    pub fn new(
+        initial_state: State,
+        update: Update,
+        view: View,
+        width: u32,
+        height: u32,
+        clear_color: Color,
+        timer: Timer,
+    ) -> Self {
+        let runtime = Runtime::new(
+            initial_state,
+            update,
+            view,
+            width,
+            height,
+            clear_color,
+            timer,
+        );
+        let mut frame = vec![0; width as usize * height as usize * 4];
+        runtime.draw(&mut frame);
+
+        Self {
+            runtime,
+            frame,
+            width,
+            height,
+            prev_mouse_pos: None,
+        }
+    }
+
+    /// Feeds one frame of input to the app and redraws.
+    ///
+    /// `prev_mouse_pos` is filled in from the previous step, the same way the windowed runtime
+    /// does it, so scripts only need to say where the mouse is now.
+    pub fn step(&mut self, input: &Input, elapsed: Duration) {
+        let input = Input {
+            prev_mouse_pos: self.prev_mouse_pos,
+            ..input.clone()
+        };
+        self.runtime.handle_input(&input, elapsed);
+        self.runtime.draw(&mut self.frame);
+        self.prev_mouse_pos = input.mouse_pos;
+    }
+
+    /// Plays back a script of input frames, each paired with the time it took.
+    pub fn run<I>(&mut self, script: I)
+    where
+        I: IntoIterator<Item = (Input, Duration)>,
+    {
+        for (input, elapsed) in script {
+            self.step(&input, elapsed);
+        }
+    }
+
+    pub fn state(&self) -> &State {
+        &self.runtime.state
+    }
+
+    /// Everything that didn't fit the last time the view was laid out, so tests can check that
+    /// a view fits the screen.
+    pub fn layout_errors(&self) -> &[LayoutError] {
+        self.runtime.layout_errors()
+    }
+
+    /// The most recently drawn frame, as tightly packed RGBA rows.
+    pub fn frame(&self) -> &[u8] {
+        &self.frame
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The RGBA color at `(x, y)` in the most recently drawn frame.
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let i = (x + y * self.width) as usize * 4;
+        self.frame[i..(i + 4)].try_into().unwrap()
+    }
+
+    pub fn to_image(&self) -> RgbaImage {
+        RgbaImage::from_raw(self.width, self.height, self.frame.clone())
+            .expect("frame buffer matches its dimensions")
+    }
+}
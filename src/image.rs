@@ -1,10 +1,14 @@
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
-use crate::constants::WIDTH;
+use std::path::Path;
+
+use image::{ImageResult, RgbaImage};
+
+use crate::buffer::Buffer;
 use crate::element::Element;
 use crate::layout::CalculatedLayout;
-use crate::palette::color_from_index;
+use crate::palette::{color_from_index, nearest_index, Color};
 use crate::state_tree::StateNode;
 
 #[derive(Clone)]
@@ -15,6 +19,33 @@ pub struct Image<T> {
     pub width: usize,
     /// image height before scaling
     pub height: usize,
+    /// The part of the image that gets drawn, or all of it when `None`
+    pub crop: Option<CalculatedLayout>,
+    /// Mirrors the (cropped) image left to right, before it is rotated
+    pub flip_horizontal: bool,
+    /// Mirrors the (cropped) image top to bottom, before it is rotated
+    pub flip_vertical: bool,
+    /// How many times the image is turned 90° clockwise
+    pub quarter_turns: u8,
+    pub fit: Fit,
+}
+
+/// How an [`Image`] is sized and placed in the region layout gives it.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Fit {
+    /// Drawn at its scale factor from the top left corner. The image needs at least that much
+    /// room.
+    #[default]
+    None,
+    /// Drawn at its scale factor in the middle of the region. The image needs at least that much
+    /// room.
+    Center,
+    /// Scaled up by the largest whole number that fits the region, and centered, so every image
+    /// pixel stays the same size. Needs room for the image at its original size.
+    IntegerFit,
+    /// Scaled as large as fits the region while keeping its aspect ratio, and centered. Pixels
+    /// may end up different sizes. Needs no room of its own, so give it a growing length.
+    Contain,
 }
 
 impl<T> Image<T> {
@@ -24,6 +55,11 @@ impl<T> Image<T> {
             width,
             height,
             scale_factor: 1,
+            crop: None,
+            flip_horizontal: false,
+            flip_vertical: false,
+            quarter_turns: 0,
+            fit: Fit::None,
         }
     }
     pub fn scale_factor(mut self, scale_factor: usize) -> Self {
@@ -31,6 +67,151 @@ impl<T> Image<T> {
         self.scale_factor = scale_factor;
         self
     }
+
+    /// Only draws the `width` by `height` rectangle at (`x`, `y`), like one icon out of a set.
+    pub fn crop(mut self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        assert!(
+            x.checked_add(width).is_some_and(|r| r <= self.width as u32)
+                && y.checked_add(height).is_some_and(|b| b <= self.height as u32),
+            "crop goes past the edge of the image"
+        );
+        self.crop = Some(CalculatedLayout {
+            x,
+            y,
+            w: width,
+            h: height,
+        });
+        self
+    }
+
+    pub fn flip_horizontal(mut self) -> Self {
+        self.flip_horizontal = !self.flip_horizontal;
+        self
+    }
+
+    pub fn flip_vertical(mut self) -> Self {
+        self.flip_vertical = !self.flip_vertical;
+        self
+    }
+
+    /// Turns the image 90° clockwise `quarter_turns` times, on top of any turns so far.
+    pub fn rotate(mut self, quarter_turns: u8) -> Self {
+        self.quarter_turns = (self.quarter_turns + quarter_turns % 4) % 4;
+        self
+    }
+
+    pub fn fit(mut self, fit: Fit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// The part of the image that gets drawn
+    fn cropped(&self) -> CalculatedLayout {
+        self.crop.unwrap_or(CalculatedLayout {
+            x: 0,
+            y: 0,
+            w: self.width as u32,
+            h: self.height as u32,
+        })
+    }
+
+    /// Width and height once cropped and rotated, before scaling
+    fn transformed_size(&self) -> (u32, u32) {
+        let crop = self.cropped();
+        match self.quarter_turns % 2 {
+            0 => (crop.w, crop.h),
+            _ => (crop.h, crop.w),
+        }
+    }
+
+    /// Where in `region` the image goes, at what size, after fitting it.
+    fn placement(&self, region: CalculatedLayout) -> CalculatedLayout {
+        let (width, height) = self.transformed_size();
+        let scale = self.scale_factor as u32;
+        let (w, h) = match self.fit {
+            Fit::None | Fit::Center => (width * scale, height * scale),
+            Fit::IntegerFit => {
+                let scale = (region.w / width.max(1))
+                    .min(region.h / height.max(1))
+                    .max(1);
+                (width * scale, height * scale)
+            }
+            Fit::Contain if width == 0 || height == 0 => (0, 0),
+            // compare the aspect ratios without dividing
+            Fit::Contain if region.w as u64 * height as u64 <= region.h as u64 * width as u64 => (
+                region.w,
+                (height as u64 * region.w as u64 / width as u64) as u32,
+            ),
+            Fit::Contain => (
+                (width as u64 * region.h as u64 / height as u64) as u32,
+                region.h,
+            ),
+        };
+        let (x, y) = match self.fit {
+            Fit::None => (region.x, region.y),
+            _ => (
+                region.x + region.w.saturating_sub(w) / 2,
+                region.y + region.h.saturating_sub(h) / 2,
+            ),
+        };
+        CalculatedLayout { x, y, w, h }
+    }
+
+    /// Index into `data` of the pixel shown at (`u`, `v`) of the cropped, flipped and rotated
+    /// image.
+    fn source_index(&self, u: u32, v: u32) -> usize {
+        let crop = self.cropped();
+        let (last_x, last_y) = (crop.w - 1, crop.h - 1);
+        // undo the rotation
+        let (mut x, mut y) = match self.quarter_turns % 4 {
+            0 => (u, v),
+            1 => (v, last_y - u),
+            2 => (last_x - u, last_y - v),
+            _ => (last_x - v, u),
+        };
+        if self.flip_horizontal {
+            x = last_x - x;
+        }
+        if self.flip_vertical {
+            y = last_y - y;
+        }
+        (crop.x + x) as usize + (crop.y + y) as usize * self.width
+    }
+}
+
+impl Image<Color> {
+    /// Decodes a PNG, GIF, BMP or any other format the `image` crate knows, from a file.
+    /// Animated formats give their first frame.
+    pub fn open(path: impl AsRef<Path>) -> ImageResult<Self> {
+        Ok(image::open(path)?.into_rgba8().into())
+    }
+
+    /// Same as [`Image::open`], for images already in memory, like ones from `include_bytes!`.
+    pub fn from_bytes(bytes: &[u8]) -> ImageResult<Self> {
+        Ok(image::load_from_memory(bytes)?.into_rgba8().into())
+    }
+
+    /// Replaces every color with the closest one in the [palette](crate::palette), keeping its
+    /// alpha.
+    pub fn quantize(self) -> Self {
+        let data = self
+            .data
+            .iter()
+            .map(|pixel| {
+                let [r, g, b, _] = color_from_index(nearest_index(*pixel));
+                [r, g, b, pixel[3]]
+            })
+            .collect();
+        Self { data, ..self }
+    }
+}
+
+impl From<RgbaImage> for Image<Color> {
+    fn from(image: RgbaImage) -> Self {
+        let (width, height) = image.dimensions();
+        let data = image.pixels().map(|pixel| pixel.0).collect();
+        Self::new(data, width as usize, height as usize)
+    }
 }
 
 trait PixelColor {
@@ -50,44 +231,36 @@ impl PixelColor for usize {
 }
 
 impl<Message, T: PixelColor + Clone> Element<Message> for Image<T> {
-    fn draw(&self, frame: &mut [u8], region: CalculatedLayout) {
-        for j in 0..self.height {
-            for i in 0..self.width {
-                let frame_index = ((region.x as usize + i * self.scale_factor)
-                    + (region.y as usize + j * self.scale_factor) * WIDTH as usize)
-                    * 4;
-
-                let pixel = self.data[i + j * self.width].get_pixel_color();
-                if frame_index + 4 < frame.len() {
-                    // our current workaround for out of
-                    // bounds crashing
-                    match self.scale_factor {
-                        1 => {
-                            //TODO: look up actual pixel value
-                            frame[frame_index..(frame_index + 4)].copy_from_slice(&pixel);
-                        }
-                        _ => {
-                            for sj in 0..self.scale_factor {
-                                for si in 0..self.scale_factor {
-                                    let scaled_frame_index =
-                                        frame_index + (si + sj * WIDTH as usize) * 4;
-                                    frame[scaled_frame_index..(scaled_frame_index + 4)]
-                                        .copy_from_slice(&pixel);
-                                }
-                            }
-                        }
-                    };
-                }
+    fn draw(&self, frame: &mut Buffer, _tree: &StateNode, region: CalculatedLayout) {
+        let (width, height) = self.transformed_size();
+        let placed = self.placement(region);
+        // only visit the pixels that can actually be drawn
+        let visible = placed.intersection(&frame.clip());
+
+        for y in visible.y..(visible.y + visible.h) {
+            let v = ((y - placed.y) as u64 * height as u64 / placed.h as u64) as u32;
+            for x in visible.x..(visible.x + visible.w) {
+                let u = ((x - placed.x) as u64 * width as u64 / placed.w as u64) as u32;
+                let pixel = self.data[self.source_index(u, v)].get_pixel_color();
+                frame.set_pixel(x as usize, y as usize, pixel);
             }
         }
     }
 
     fn min_width(&self) -> u32 {
-        (self.width * self.scale_factor) as u32
+        match self.fit {
+            Fit::None | Fit::Center => self.transformed_size().0 * self.scale_factor as u32,
+            Fit::IntegerFit => self.transformed_size().0,
+            Fit::Contain => 0,
+        }
     }
 
     fn min_height(&self, _width: u32) -> u32 {
-        (self.height * self.scale_factor) as u32
+        match self.fit {
+            Fit::None | Fit::Center => self.transformed_size().1 * self.scale_factor as u32,
+            Fit::IntegerFit => self.transformed_size().1,
+            Fit::Contain => 0,
+        }
     }
 
     fn get_message(
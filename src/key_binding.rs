@@ -0,0 +1,90 @@
+use crate::{
+    buffer::Buffer,
+    element::Element,
+    layout::{CalculatedLayout, Layout},
+    state_tree::StateNode,
+    tree::Node,
+    Input, KeyCode, Modifiers,
+};
+
+/// A key along with the modifiers that have to be held for it to count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shortcut {
+    pub key: KeyCode,
+    pub modifiers: Modifiers,
+}
+
+impl Shortcut {
+    pub fn new(modifiers: Modifiers, key: KeyCode) -> Self {
+        Self { key, modifiers }
+    }
+
+    pub fn shift(key: KeyCode) -> Self {
+        Self::new(Modifiers::SHIFT, key)
+    }
+
+    pub fn control(key: KeyCode) -> Self {
+        Self::new(Modifiers::CONTROL, key)
+    }
+
+    pub fn alt(key: KeyCode) -> Self {
+        Self::new(Modifiers::ALT, key)
+    }
+
+    /// Pressed this frame, with exactly these modifiers held.
+    pub fn pressed(&self, input: &Input) -> bool {
+        input.key_pressed(self.key) && input.modifiers == self.modifiers
+    }
+}
+
+impl From<KeyCode> for Shortcut {
+    fn from(key: KeyCode) -> Self {
+        Self::new(Modifiers::NONE, key)
+    }
+}
+
+/// Turns key presses into messages, no matter where the mouse is.
+///
+/// Bindings are checked in the order they were added.
+pub struct KeyBinding<Message> {
+    bindings: Vec<(Shortcut, Message)>,
+}
+
+impl<Message> Default for KeyBinding<Message> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Message> KeyBinding<Message> {
+    pub fn new() -> Self {
+        Self { bindings: vec![] }
+    }
+
+    pub fn on_key(mut self, shortcut: impl Into<Shortcut>, m: Message) -> Self {
+        self.bindings.push((shortcut.into(), m));
+        self
+    }
+}
+
+impl<Message: Clone> Element<Message> for KeyBinding<Message> {
+    fn draw(&self, _frame: &mut Buffer, _tree: &StateNode, _region: CalculatedLayout) {}
+
+    fn get_message(
+        &mut self,
+        _tree: &mut StateNode,
+        input: &Input,
+        _region: CalculatedLayout,
+    ) -> Option<Message> {
+        self.bindings
+            .iter()
+            .find(|(shortcut, _)| shortcut.pressed(input))
+            .map(|(_, m)| m.clone())
+    }
+}
+
+impl<'a, Message: Clone + 'static> From<KeyBinding<Message>> for Node<'a, Message, Layout> {
+    fn from(value: KeyBinding<Message>) -> Self {
+        Node::new(value)
+    }
+}
@@ -2,36 +2,69 @@ use std::cmp::max;
 
 use crate::tree::Node;
 
+mod error;
+mod grid;
 mod length_types;
 mod padding;
 mod pipeline_types;
+pub use error::{Axis, LayoutError};
+use grid::{spanned_length, track_starts};
+pub use grid::{Grid, GridCell};
 pub use length_types::Length;
-use length_types::{GrownLength, ShrunkLength, XY};
+use length_types::{GrownLength, Limits, ShrunkLength};
 pub use padding::Padding;
-pub use pipeline_types::{CalculatedLayout, Layout, LayoutDirection};
+pub use pipeline_types::{Align, Anchor, CalculatedLayout, Justify, Layout, LayoutDirection};
 use pipeline_types::{GrownHeightLayout, GrownWidthLayout, ShrinkHeightLayout, ShrinkWidthLayout};
 
 // advice from Clay https://www.youtube.com/watch?v=by9lQvpvMIc
 // fit sizing widths
 // grow and shrink sizing widths
-// wrap text
+// wrap text and wrapping containers
 // fit sizing heights
 // grow and shrink sizing heights
 // positions
 // draw commands
 
 impl<'a, Message> Node<'a, Message, Layout> {
+    /// Sizes and positions every node, falling back as described on [`LayoutError`] when the
+    /// tree doesn't fit.
     pub fn calculate_layout(self) -> Node<'a, Message, CalculatedLayout> {
+        self.calculate_layout_reporting(&mut vec![])
+    }
+
+    /// Same as [`Node::calculate_layout`], but fails with the first thing that didn't fit.
+    pub fn try_calculate_layout(self) -> Result<Node<'a, Message, CalculatedLayout>, LayoutError> {
+        let mut errors = vec![];
+        let root = self.calculate_layout_reporting(&mut errors);
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(root),
+        }
+    }
+
+    /// Same as [`Node::calculate_layout`], collecting everything that didn't fit into `errors`.
+    pub(crate) fn calculate_layout_reporting(
+        self,
+        errors: &mut Vec<LayoutError>,
+    ) -> Node<'a, Message, CalculatedLayout> {
         // TODO: Use better types for root node, so we don't have to match for unsupported root
         // node length types
-        let root_size = match (self.layout.width, self.layout.height) {
-            (Length::Fixed(w), Length::Fixed(h)) => XY(w, h),
-            (_, _) => panic!("root size must be a fixed length"),
-        };
-        self.shrink_width_pass()
-            .grow_width_pass(root_size.0) /*.wrap()*/
-            .shrink_height_pass()
-            .grow_height_pass(root_size.1)
+        if !matches!(
+            (self.layout.width, self.layout.height),
+            (Length::Fixed(_), Length::Fixed(_))
+        ) {
+            errors.push(LayoutError::RootNotFixed);
+        }
+        // a fixed root shrinks to exactly its size, anything else to fit its content
+        let root = self.shrink_width_pass();
+        let root_width = root.layout.width.min_length();
+        let root = root
+            // children of `Wrap` nodes are split into lines as soon as their widths are known,
+            // and every later pass splits them the same way
+            .grow_width_pass(root_width, errors)
+            .shrink_height_pass();
+        let root_height = root.layout.height.min_length();
+        root.grow_height_pass(root_height, errors)
             .position_pass((0, 0))
     }
 
@@ -39,25 +72,33 @@ impl<'a, Message> Node<'a, Message, Layout> {
     /// bottom-up pass
     fn shrink_width_pass(self) -> Node<'a, Message, ShrinkWidthLayout> {
         self.element.min_width();
+        let stretch = self.layout.stretches_width();
         let new_children: Vec<_> = self
             .children
             .into_iter()
-            .map(|c| c.shrink_width_pass())
+            .map(|c| {
+                let shrinks = matches!(c.layout.width, Length::Shrink)
+                    && !self.layout.floats(c.layout.anchor);
+                let mut child = c.shrink_width_pass();
+                if let (true, true, ShrunkLength::Fixed(l)) = (stretch, shrinks, child.layout.width)
+                {
+                    child.layout.width = ShrunkLength::grow(1, l);
+                }
+                child
+            })
             .collect();
 
-        let new_children_widths = new_children.iter().map(|child| match child.layout.width {
-            ShrunkLength::Grow => 0,
-            ShrunkLength::GrowWithMin(m) => m,
-            ShrunkLength::Fixed(l) => l,
-        });
+        let new_children_widths = new_children
+            .iter()
+            .map(|child| child.layout.width.min_length());
         let new_width = match self.layout.width {
-            Length::Grow => match self.element.min_width() {
-                0 => ShrunkLength::Grow,
-                w => ShrunkLength::GrowWithMin(w),
-            },
+            Length::Grow => ShrunkLength::grow(1, self.element.min_width()),
+            Length::FillPortion(portion) => ShrunkLength::grow(portion, self.element.min_width()),
+            Length::Percent(p) => ShrunkLength::Percent(p),
             Length::Fixed(l) => ShrunkLength::Fixed(l),
             Length::Shrink => match self.layout.direction {
-                LayoutDirection::Row => {
+                // a shrinking wrap only breaks lines when its max width holds it back
+                LayoutDirection::Row | LayoutDirection::Wrap => {
                     // Sum widths of children
                     let l: u32 = new_children_widths.sum();
                     let total_spacing =
@@ -72,28 +113,63 @@ impl<'a, Message> Node<'a, Message, Layout> {
                 }
                 LayoutDirection::Column | LayoutDirection::Stack => {
                     let element_min_width = self.element.min_width();
-                    // Get max child width
-                    let max_child_cross_length: u32 = new_children_widths.max().unwrap_or(0);
+                    // Get max child width, leaving out children floating over a stack
+                    let max_child_cross_length: u32 = new_children
+                        .iter()
+                        .zip(new_children_widths)
+                        .filter(|(c, _)| !self.layout.floats(c.layout.anchor))
+                        .map(|(_, width)| width)
+                        .max()
+                        .unwrap_or(0);
                     ShrunkLength::Fixed(
                         (max_child_cross_length).max(element_min_width)
                             + self.layout.padding.left
                             + self.layout.padding.right,
                     )
                 }
+                LayoutDirection::Grid => {
+                    let cells: Vec<_> = new_children.iter().map(|c| c.layout.cell).collect();
+                    let min_lengths: Vec<_> = new_children_widths.collect();
+                    let (_, tracks) =
+                        self.layout
+                            .grid_tracks(Axis::Horizontal, &cells, &min_lengths);
+                    ShrunkLength::Fixed(
+                        self.layout
+                            .grid_needed_length(&tracks)
+                            .max(self.element.min_width())
+                            + self.layout.padding.left
+                            + self.layout.padding.right,
+                    )
+                }
             },
         };
 
+        let new_width = self.layout.width_limits().apply(new_width);
+
         Node {
             layout: ShrinkWidthLayout {
                 width: new_width,
                 height: self.layout.height, // keep old height
+                min_width: self.layout.min_width,
+                max_width: self.layout.max_width,
+                min_height: self.layout.min_height,
+                max_height: self.layout.max_height,
+                cell: self.layout.cell,
+                anchor: self.layout.anchor,
+                offset: self.layout.offset,
                 padding: self.layout.padding,
                 direction: self.layout.direction,
                 spacing: self.layout.spacing,
+                justify: self.layout.justify,
+                align: self.layout.align,
+                scroll: self.layout.scroll,
+                grid: self.layout.grid,
             },
             children: new_children,
             element: self.element,
             name: self.name,
+            key: self.key,
+            z_index: self.z_index,
         }
     }
 }
@@ -102,25 +178,66 @@ impl<'a, Message> Node<'a, Message, GrownWidthLayout> {
     /// Render pass 1/3
     /// bottom-up pass
     fn shrink_height_pass(self) -> Node<'a, Message, ShrinkHeightLayout> {
+        let stretch = self.layout.stretches_height();
         let new_children: Vec<_> = self
             .children
             .into_iter()
-            .map(|c| c.shrink_height_pass())
+            .map(|c| {
+                let shrinks = matches!(c.layout.height, Length::Shrink)
+                    && !self.layout.floats(c.layout.anchor);
+                let mut child = c.shrink_height_pass();
+                if let (true, true, ShrunkLength::Fixed(l)) =
+                    (stretch, shrinks, child.layout.height)
+                {
+                    child.layout.height = ShrunkLength::grow(1, l);
+                }
+                child
+            })
             .collect();
 
-        let new_children_heights = new_children.iter().map(|child| match child.layout.height {
-            ShrunkLength::Grow => 0,
-            ShrunkLength::GrowWithMin(l) => l,
-            ShrunkLength::Fixed(l) => l,
-        });
-        let new_height = match self.layout.height {
-            Length::Grow => match max(
-                new_children_heights.max().unwrap_or_default(),
+        let new_children_heights = new_children
+            .iter()
+            .map(|child| child.layout.height.min_length());
+        // height of the children when they don't simply sit in a row or column
+        let arranged_height = match self.layout.direction {
+            LayoutDirection::Grid => {
+                let cells: Vec<_> = new_children.iter().map(|c| c.layout.cell).collect();
+                let min_lengths: Vec<_> = new_children_heights.clone().collect();
+                let (_, tracks) = self
+                    .layout
+                    .grid_tracks(Axis::Vertical, &cells, &min_lengths);
+                Some(self.layout.grid_needed_length(&tracks))
+            }
+            LayoutDirection::Wrap => {
+                let sizes: Vec<_> = new_children
+                    .iter()
+                    .zip(new_children_heights.clone())
+                    .map(|(c, height)| (c.layout.width, height))
+                    .collect();
+                let inner_width = self
+                    .layout
+                    .width
+                    .saturating_sub(self.layout.padding.left + self.layout.padding.right);
+                let lines = self.layout.wrap_lines(&sizes, inner_width);
+                Some(self.layout.wrap_needed_height(&lines))
+            }
+            _ => None,
+        };
+        // scrolling content doesn't need to fit
+        let grow_min = match (self.layout.scroll, arranged_height) {
+            (true, _) => self.element.min_height(self.layout.width),
+            (false, Some(arranged_height)) => {
+                max(arranged_height, self.element.min_height(self.layout.width))
+            }
+            (false, None) => max(
+                new_children_heights.clone().max().unwrap_or_default(),
                 self.element.min_height(self.layout.width),
-            ) {
-                0 => ShrunkLength::Grow,
-                h => ShrunkLength::GrowWithMin(h),
-            },
+            ),
+        };
+        let new_height = match self.layout.height {
+            Length::Grow => ShrunkLength::grow(1, grow_min),
+            Length::FillPortion(portion) => ShrunkLength::grow(portion, grow_min),
+            Length::Percent(p) => ShrunkLength::Percent(p),
             Length::Fixed(l) => ShrunkLength::Fixed(l),
             Length::Shrink => match self.layout.direction {
                 LayoutDirection::Column => {
@@ -135,28 +252,56 @@ impl<'a, Message> Node<'a, Message, GrownWidthLayout> {
                     )
                 }
                 LayoutDirection::Row | LayoutDirection::Stack => {
-                    // Get max child height
-                    let max_child_cross_length: u32 = new_children_heights.max().unwrap_or(0);
+                    // Get max child height, leaving out children floating over a stack
+                    let max_child_cross_length: u32 = new_children
+                        .iter()
+                        .zip(new_children_heights)
+                        .filter(|(c, _)| !self.layout.floats(c.layout.anchor))
+                        .map(|(_, height)| height)
+                        .max()
+                        .unwrap_or(0);
                     ShrunkLength::Fixed(
                         max_child_cross_length.max(self.element.min_height(self.layout.width))
                             + self.layout.padding.top
                             + self.layout.padding.bottom,
                     )
                 }
+                LayoutDirection::Grid | LayoutDirection::Wrap => ShrunkLength::Fixed(
+                    arranged_height
+                        .unwrap_or_default()
+                        .max(self.element.min_height(self.layout.width))
+                        + self.layout.padding.top
+                        + self.layout.padding.bottom,
+                ),
             },
         };
 
+        let new_height = self.layout.height_limits().apply(new_height);
+
         Node {
             layout: ShrinkHeightLayout {
                 width: self.layout.width,
                 height: new_height,
+                min_width: self.layout.min_width,
+                max_width: self.layout.max_width,
+                min_height: self.layout.min_height,
+                max_height: self.layout.max_height,
+                cell: self.layout.cell,
+                anchor: self.layout.anchor,
+                offset: self.layout.offset,
                 padding: self.layout.padding,
                 direction: self.layout.direction,
                 spacing: self.layout.spacing,
+                justify: self.layout.justify,
+                align: self.layout.align,
+                scroll: self.layout.scroll,
+                grid: self.layout.grid,
             },
             children: new_children,
             element: self.element,
             name: self.name,
+            key: self.key,
+            z_index: self.z_index,
         }
     }
 }
@@ -164,59 +309,78 @@ impl<'a, Message> Node<'a, Message, GrownWidthLayout> {
 impl<'a, Message> Node<'a, Message, ShrinkWidthLayout> {
     /// Render pass 2/3
     /// top-down
-    fn grow_width_pass(self, assigned_width: GrownLength) -> Node<'a, Message, GrownWidthLayout> {
+    fn grow_width_pass(
+        self,
+        assigned_width: GrownLength,
+        errors: &mut Vec<LayoutError>,
+    ) -> Node<'a, Message, GrownWidthLayout> {
+        let available_width =
+            assigned_width.saturating_sub(self.layout.padding.left + self.layout.padding.right);
+        let lengths: Vec<_> = self
+            .children
+            .iter()
+            .map(|c| (c.layout.width, c.layout.width_limits()))
+            .collect();
+        let (placements, tracks) = match self.layout.direction {
+            LayoutDirection::Grid => {
+                let cells: Vec<_> = self.children.iter().map(|c| c.layout.cell).collect();
+                let min_lengths: Vec<_> = lengths.iter().map(|(l, _)| l.min_length()).collect();
+                self.layout
+                    .grid_tracks(Axis::Horizontal, &cells, &min_lengths)
+            }
+            _ => (vec![], vec![]),
+        };
+        let needed_width = match self.layout.direction {
+            LayoutDirection::Grid => self.layout.grid_needed_length(&tracks),
+            direction => self
+                .layout
+                .needed_length(&lengths, direction == LayoutDirection::Row),
+        };
+        errors.extend(LayoutError::check_overflow(
+            &self.name,
+            Axis::Horizontal,
+            needed_width,
+            available_width,
+        ));
+        let mut column_widths = vec![];
         let new_children_widths: Vec<_> = match self.layout.direction {
-            LayoutDirection::Column | LayoutDirection::Stack => self
-                .children
+            LayoutDirection::Column | LayoutDirection::Stack => lengths
                 .iter()
-                .map(|c| match c.layout.width {
-                    ShrunkLength::Grow => {
-                        // available width
-                        assigned_width - (self.layout.padding.left + self.layout.padding.right)
-                    }
-                    ShrunkLength::GrowWithMin(l) => {
-                        let new_width =
-                            assigned_width - (self.layout.padding.left + self.layout.padding.right);
-                        assert!(new_width >= l);
-                        new_width
-                    }
-                    ShrunkLength::Fixed(l) => l,
-                })
+                .map(|(length, limits)| length.fit(available_width, *limits))
                 .collect(),
-            LayoutDirection::Row => {
-                let remaining_length = assigned_width
-                    .saturating_sub(
-                        self.children
-                            .iter()
-                            .map(|c| match c.layout.width {
-                                ShrunkLength::Grow => 0,
-                                ShrunkLength::GrowWithMin(l) => l,
-                                ShrunkLength::Fixed(l) => l,
-                            })
-                            .sum::<u32>(),
-                    )
-                    .saturating_sub(self.layout.padding.left + self.layout.padding.right)
-                    .saturating_sub(
-                        self.layout.spacing * self.children.len().saturating_sub(1) as u32,
-                    );
-                let child_grow_number: u32 = self
-                    .children
+            LayoutDirection::Row => self.layout.flow_lengths(&lengths, available_width),
+            LayoutDirection::Wrap => {
+                // lines are broken before anything grows, then each grows like its own row
+                let sizes: Vec<_> = lengths
                     .iter()
-                    .filter(|c| match c.layout.width {
-                        ShrunkLength::Grow => true,
-                        ShrunkLength::GrowWithMin(_) => true,
-                        ShrunkLength::Fixed(_) => false,
+                    .map(|(length, limits)| match length {
+                        ShrunkLength::Percent(_) => (length.fit(available_width, *limits), 0),
+                        _ => (length.min_length(), 0),
                     })
-                    .count()
-                    .try_into()
-                    .unwrap();
-
-                self.children
+                    .collect();
+                self.layout
+                    .wrap_lines(&sizes, available_width)
+                    .into_iter()
+                    .flat_map(|(line, _)| self.layout.flow_lengths(&lengths[line], available_width))
+                    .collect()
+            }
+            LayoutDirection::Grid => {
+                let tracks: Vec<_> = tracks
                     .iter()
-                    .map(|c| match c.layout.width {
-                        ShrunkLength::Grow => remaining_length / child_grow_number,
-                        ShrunkLength::GrowWithMin(l) => l + (remaining_length / child_grow_number),
-                        ShrunkLength::Fixed(l) => l,
+                    .map(|t| (*t, Limits::new(None, None)))
+                    .collect();
+                column_widths = self.layout.flow_lengths(&tracks, available_width);
+                placements
+                    .iter()
+                    .zip(&lengths)
+                    .map(|(p, (length, limits))| {
+                        let cell_width = spanned_length(
+                            &column_widths,
+                            p.column,
+                            p.column_span,
+                            self.layout.spacing,
+                        );
+                        length.fit(cell_width, *limits)
                     })
                     .collect()
             }
@@ -226,20 +390,36 @@ impl<'a, Message> Node<'a, Message, ShrinkWidthLayout> {
             .children
             .into_iter()
             .zip(new_children_widths)
-            .map(|(c, width)| c.grow_width_pass(width))
+            .map(|(c, width)| c.grow_width_pass(width, errors))
             .collect();
 
         Node {
             layout: GrownWidthLayout {
                 width: assigned_width,
                 height: self.layout.height,
+                min_width: self.layout.min_width,
+                max_width: self.layout.max_width,
+                min_height: self.layout.min_height,
+                max_height: self.layout.max_height,
+                cell: self.layout.cell,
+                anchor: self.layout.anchor,
+                offset: self.layout.offset,
                 padding: self.layout.padding,
                 direction: self.layout.direction,
                 spacing: self.layout.spacing,
+                justify: self.layout.justify,
+                align: self.layout.align,
+                scroll: self.layout.scroll,
+                grid: Grid {
+                    column_widths,
+                    ..self.layout.grid
+                },
             },
             children: new_children,
             element: self.element,
             name: self.name,
+            key: self.key,
+            z_index: self.z_index,
         }
     }
 }
@@ -250,78 +430,124 @@ impl<'a, Message> Node<'a, Message, ShrinkHeightLayout> {
     fn grow_height_pass(
         self,
         assigned_height: GrownLength,
+        errors: &mut Vec<LayoutError>,
     ) -> Node<'a, Message, GrownHeightLayout> {
-        let flow_cross_padding = self.layout.summed_padding();
-
-        let child_grow_number: u32 = self
+        let mut available_height =
+            assigned_height.saturating_sub(self.layout.padding.top + self.layout.padding.bottom);
+        let lengths: Vec<_> = self
             .children
             .iter()
-            .filter(|c| match self.layout.direction {
-                LayoutDirection::Column => match c.layout.height {
-                    ShrunkLength::Grow => true,
-                    ShrunkLength::GrowWithMin(_) => true,
-                    ShrunkLength::Fixed(_) => false,
-                },
-                LayoutDirection::Row | LayoutDirection::Stack => false,
-            })
-            .count()
-            .try_into()
-            .unwrap();
-
-        let remaining_length = assigned_height
-            .saturating_sub(
-                self.children
+            .map(|c| (c.layout.height, c.layout.height_limits()))
+            .collect();
+        let (placements, tracks) = match self.layout.direction {
+            LayoutDirection::Grid => {
+                let cells: Vec<_> = self.children.iter().map(|c| c.layout.cell).collect();
+                let min_lengths: Vec<_> = lengths.iter().map(|(l, _)| l.min_length()).collect();
+                self.layout
+                    .grid_tracks(Axis::Vertical, &cells, &min_lengths)
+            }
+            _ => (vec![], vec![]),
+        };
+        let lines = match self.layout.direction {
+            LayoutDirection::Wrap => {
+                let sizes: Vec<_> = self
+                    .children
                     .iter()
-                    .map(|c| match self.layout.direction {
-                        LayoutDirection::Column => match c.layout.height {
-                            ShrunkLength::Grow => 0,
-                            ShrunkLength::GrowWithMin(l) => l, // probably copy width logic
-                            ShrunkLength::Fixed(l) => l,
-                        },
-                        LayoutDirection::Row | LayoutDirection::Stack => c.layout.width,
+                    .zip(&lengths)
+                    .map(|(c, (length, _))| (c.layout.width, length.min_length()))
+                    .collect();
+                let inner_width = self
+                    .layout
+                    .width
+                    .saturating_sub(self.layout.padding.left + self.layout.padding.right);
+                self.layout.wrap_lines(&sizes, inner_width)
+            }
+            _ => vec![],
+        };
+        let needed_height = match self.layout.direction {
+            LayoutDirection::Grid => self.layout.grid_needed_length(&tracks),
+            LayoutDirection::Wrap => self.layout.wrap_needed_height(&lines),
+            direction => self
+                .layout
+                .needed_length(&lengths, direction == LayoutDirection::Column),
+        };
+        if self.layout.scroll {
+            available_height = max(available_height, needed_height);
+        } else {
+            errors.extend(LayoutError::check_overflow(
+                &self.name,
+                Axis::Vertical,
+                needed_height,
+                available_height,
+            ));
+        }
+        let mut row_heights = vec![];
+        let new_children_heights: Vec<_> = match self.layout.direction {
+            LayoutDirection::Row | LayoutDirection::Stack => lengths
+                .iter()
+                .map(|(length, limits)| length.fit(available_height, *limits))
+                .collect(),
+            LayoutDirection::Column => self.layout.flow_lengths(&lengths, available_height),
+            LayoutDirection::Wrap => lines
+                .into_iter()
+                .flat_map(|(line, line_height)| {
+                    lengths[line]
+                        .iter()
+                        .map(move |(length, limits)| length.fit(line_height, *limits))
+                })
+                .collect(),
+            LayoutDirection::Grid => {
+                let tracks: Vec<_> = tracks
+                    .iter()
+                    .map(|t| (*t, Limits::new(None, None)))
+                    .collect();
+                row_heights = self.layout.flow_lengths(&tracks, available_height);
+                placements
+                    .iter()
+                    .zip(&lengths)
+                    .map(|(p, (length, limits))| {
+                        let cell_height =
+                            spanned_length(&row_heights, p.row, p.row_span, self.layout.spacing);
+                        length.fit(cell_height, *limits)
                     })
-                    .sum::<u32>(),
-            )
-            .saturating_sub(flow_cross_padding.0)
-            .saturating_sub(self.layout.spacing * self.children.len().saturating_sub(1) as u32);
+                    .collect()
+            }
+        };
+
         let new_children: Vec<_> = self
             .children
             .into_iter()
-            .map(|c| {
-                let child_height = match (self.layout.direction, c.layout.height) {
-                    (LayoutDirection::Column, ShrunkLength::Grow) => {
-                        remaining_length / child_grow_number
-                    }
-                    (LayoutDirection::Column, ShrunkLength::GrowWithMin(m)) => {
-                        m + (remaining_length / child_grow_number)
-                    }
-
-                    (LayoutDirection::Row | LayoutDirection::Stack, ShrunkLength::Grow) => {
-                        assigned_height - flow_cross_padding.1
-                    }
-                    (
-                        LayoutDirection::Row | LayoutDirection::Stack,
-                        ShrunkLength::GrowWithMin(m),
-                    ) => max(m, assigned_height - flow_cross_padding.1),
-
-                    (_, ShrunkLength::Fixed(l)) => l,
-                };
-
-                c.grow_height_pass(child_height)
-            })
+            .zip(new_children_heights)
+            .map(|(c, height)| c.grow_height_pass(height, errors))
             .collect();
 
         Node {
             layout: GrownHeightLayout {
                 width: self.layout.width,
                 height: assigned_height,
+                min_width: self.layout.min_width,
+                max_width: self.layout.max_width,
+                min_height: self.layout.min_height,
+                max_height: self.layout.max_height,
+                cell: self.layout.cell,
+                anchor: self.layout.anchor,
+                offset: self.layout.offset,
                 padding: self.layout.padding,
                 direction: self.layout.direction,
                 spacing: self.layout.spacing,
+                justify: self.layout.justify,
+                align: self.layout.align,
+                scroll: self.layout.scroll,
+                grid: Grid {
+                    row_heights,
+                    ..self.layout.grid
+                },
             },
             children: new_children,
             element: self.element,
             name: self.name,
+            key: self.key,
+            z_index: self.z_index,
         }
     }
 }
@@ -333,31 +559,146 @@ impl<'a, Message> Node<'a, Message, GrownHeightLayout> {
             parent_position.0 + self.layout.padding.left,
             parent_position.1 + self.layout.padding.top,
         );
+        let inner_width = self
+            .layout
+            .width
+            .saturating_sub(self.layout.padding.left + self.layout.padding.right);
+        let inner_height = self
+            .layout
+            .height
+            .saturating_sub(self.layout.padding.top + self.layout.padding.bottom);
+
+        let child_count = self.children.len() as u32;
+        let total_spacing = child_count.saturating_sub(1) * self.layout.spacing;
+        // leftover space along the flow direction, for justification
+        let main_free = match self.layout.direction {
+            LayoutDirection::Row => inner_width.saturating_sub(
+                self.children.iter().map(|c| c.layout.width).sum::<u32>() + total_spacing,
+            ),
+            LayoutDirection::Column => inner_height.saturating_sub(
+                self.children.iter().map(|c| c.layout.height).sum::<u32>() + total_spacing,
+            ),
+            LayoutDirection::Stack | LayoutDirection::Grid | LayoutDirection::Wrap => 0,
+        };
+        let justify = self.layout.justify;
+        let align = self.layout.align;
+
+        let cells: Vec<_> = self.children.iter().map(|c| c.layout.cell).collect();
+        let placements = match self.layout.direction {
+            LayoutDirection::Grid => self.layout.grid.place(&cells),
+            _ => vec![],
+        };
+        let grid = &self.layout.grid;
+        let column_starts = track_starts(&grid.column_widths, self.layout.spacing);
+        let row_starts = track_starts(&grid.row_heights, self.layout.spacing);
+        let wrap_offsets = match self.layout.direction {
+            LayoutDirection::Wrap => {
+                let sizes: Vec<_> = self
+                    .children
+                    .iter()
+                    .map(|c| (c.layout.width, c.layout.height))
+                    .collect();
+                self.layout.wrap_offsets(&sizes, inner_width)
+            }
+            _ => vec![],
+        };
+
         let new_children: Vec<_> = self
             .children
             .into_iter()
-            .scan(first_child_position, |accumulated_position, child_node| {
-                let start_position = *accumulated_position;
-                match self.layout.direction {
-                    LayoutDirection::Row => {
-                        accumulated_position.0 += child_node.layout.width + self.layout.spacing;
-                    }
-                    LayoutDirection::Column => {
-                        accumulated_position.1 += child_node.layout.height + self.layout.spacing;
-                    }
-                    LayoutDirection::Stack => (),
-                };
-                Some(child_node.position_pass(start_position))
-            })
+            .enumerate()
+            .scan(
+                first_child_position,
+                |accumulated_position, (i, child_node)| {
+                    let start_position = *accumulated_position;
+                    let (width, height) = (child_node.layout.width, child_node.layout.height);
+                    let main_offset = justify.offset(main_free, i as u32, child_count);
+                    let position = match self.layout.direction {
+                        LayoutDirection::Row => {
+                            accumulated_position.0 += width + self.layout.spacing;
+                            (
+                                start_position.0 + main_offset,
+                                start_position.1
+                                    + align.offset(inner_height.saturating_sub(height)),
+                            )
+                        }
+                        LayoutDirection::Column => {
+                            accumulated_position.1 += height + self.layout.spacing;
+                            (
+                                start_position.0 + align.offset(inner_width.saturating_sub(width)),
+                                start_position.1 + main_offset,
+                            )
+                        }
+                        LayoutDirection::Stack => match child_node.layout.anchor {
+                            Some(anchor) => anchor.position(
+                                CalculatedLayout::new(
+                                    start_position.0,
+                                    start_position.1,
+                                    inner_width,
+                                    inner_height,
+                                ),
+                                (width, height),
+                                child_node.layout.offset,
+                            ),
+                            None => (
+                                start_position.0
+                                    + justify.offset(inner_width.saturating_sub(width), 0, 1),
+                                start_position.1
+                                    + align.offset(inner_height.saturating_sub(height)),
+                            ),
+                        },
+                        LayoutDirection::Wrap => (
+                            start_position.0 + wrap_offsets[i].0,
+                            start_position.1 + wrap_offsets[i].1,
+                        ),
+                        LayoutDirection::Grid => {
+                            let p = placements[i];
+                            let spacing = self.layout.spacing;
+                            let cell_width = spanned_length(
+                                &grid.column_widths,
+                                p.column,
+                                p.column_span,
+                                spacing,
+                            );
+                            let cell_height =
+                                spanned_length(&grid.row_heights, p.row, p.row_span, spacing);
+                            (
+                                start_position.0
+                                    + column_starts[p.column]
+                                    + justify.offset(cell_width.saturating_sub(width), 0, 1),
+                                start_position.1
+                                    + row_starts[p.row]
+                                    + align.offset(cell_height.saturating_sub(height)),
+                            )
+                        }
+                    };
+                    Some(child_node.position_pass(position))
+                },
+            )
             .collect();
 
+        let mut element = self.element;
+        let content = new_children
+            .iter()
+            .map(|c| c.layout)
+            .reduce(|a, b| a.union(&b))
+            .unwrap_or(CalculatedLayout::new(
+                first_child_position.0,
+                first_child_position.1,
+                0,
+                0,
+            ));
+        element.children_positioned(content);
+
         let (x, y) = parent_position;
 
         Node {
             layout: CalculatedLayout::new(x, y, self.layout.width, self.layout.height),
             children: new_children,
-            element: self.element,
+            element,
             name: self.name,
+            key: self.key,
+            z_index: self.z_index,
         }
     }
 }
@@ -370,4 +711,22 @@ impl CalculatedLayout {
     pub fn contains(&self, pos: (u32, u32)) -> bool {
         pos.0 >= self.x && pos.0 < self.x + self.w && pos.1 >= self.y && pos.1 < self.y + self.h
     }
+
+    /// The area covered by both regions. Has zero width or height when they don't overlap.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.w).min(other.x + other.w);
+        let bottom = (self.y + self.h).min(other.y + other.h);
+        Self::new(x, y, right.saturating_sub(x), bottom.saturating_sub(y))
+    }
+
+    /// The smallest region covering both.
+    pub fn union(&self, other: &Self) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.w).max(other.x + other.w);
+        let bottom = (self.y + self.h).max(other.y + other.h);
+        Self::new(x, y, right - x, bottom - y)
+    }
 }
@@ -0,0 +1,73 @@
+use std::fmt;
+
+/// The direction a layout ran out of room in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Something [`Node::try_calculate_layout`](crate::tree::Node::try_calculate_layout) couldn't
+/// lay out the way it was asked to.
+///
+/// [`Node::calculate_layout`](crate::tree::Node::calculate_layout) carries on regardless: a root
+/// that isn't fixed shrinks to fit its content, and children that don't fit keep their minimum
+/// length and spill past their parent, where drawing clips them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LayoutError {
+    /// The root's width and height have to be [`Length::Fixed`](super::Length::Fixed).
+    RootNotFixed,
+    /// The children of the node named `node` need `needed` pixels along `axis`, but only
+    /// `available` are left inside its padding.
+    Overflow {
+        node: Option<String>,
+        axis: Axis,
+        needed: u32,
+        available: u32,
+    },
+}
+
+impl LayoutError {
+    pub(super) fn check_overflow(
+        node: &Option<String>,
+        axis: Axis,
+        needed: u32,
+        available: u32,
+    ) -> Option<Self> {
+        (needed > available).then(|| LayoutError::Overflow {
+            node: node.clone(),
+            axis,
+            needed,
+            available,
+        })
+    }
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::RootNotFixed => write!(f, "root size must be a fixed length"),
+            LayoutError::Overflow {
+                node,
+                axis,
+                needed,
+                available,
+            } => {
+                let dimension = match axis {
+                    Axis::Horizontal => "width",
+                    Axis::Vertical => "height",
+                };
+                match node {
+                    Some(name) => write!(f, "children of `{name}`")?,
+                    None => write!(f, "children of an unnamed node")?,
+                }
+                write!(
+                    f,
+                    " need {needed}px of {dimension} but only {available}px is available"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
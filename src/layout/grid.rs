@@ -0,0 +1,243 @@
+use super::{
+    error::Axis,
+    length_types::{distribute, Length, ShrunkLength},
+    pipeline_types::LayoutTemplate,
+};
+
+/// The columns and rows of a [`LayoutDirection::Grid`](super::LayoutDirection::Grid).
+///
+/// Each track is sized like a child of a row or column would be: `Fixed` tracks keep their
+/// length, `Shrink` tracks fit the largest child in them, and `Grow` and `FillPortion` tracks
+/// fit their children and then share out whatever room is left. Children placed past the last
+/// defined row or column get extra `Shrink` tracks.
+#[derive(Clone, Default, Debug)]
+pub struct Grid {
+    pub columns: Vec<Length>,
+    pub rows: Vec<Length>,
+    /// Filled in by the width passes
+    pub(super) column_widths: Vec<u32>,
+    /// Filled in by the height passes
+    pub(super) row_heights: Vec<u32>,
+}
+
+/// Which cells of its parent's grid a node covers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GridCell {
+    /// `None` here or in `row` to take the next free cell, going along each row before moving
+    /// down
+    pub column: Option<u32>,
+    pub row: Option<u32>,
+    pub column_span: u32,
+    pub row_span: u32,
+}
+
+impl Default for GridCell {
+    fn default() -> Self {
+        Self {
+            column: None,
+            row: None,
+            column_span: 1,
+            row_span: 1,
+        }
+    }
+}
+
+/// Where a child ended up in the grid, in tracks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) struct Placement {
+    pub column: usize,
+    pub row: usize,
+    pub column_span: usize,
+    pub row_span: usize,
+}
+
+impl Grid {
+    pub fn new(columns: Vec<Length>, rows: Vec<Length>) -> Self {
+        Self {
+            columns,
+            rows,
+            ..Self::default()
+        }
+    }
+
+    /// Puts each child in its cell. Children without a cell of their own go in the first free
+    /// cells they fit in, after the previous automatically placed child.
+    pub(super) fn place(&self, cells: &[GridCell]) -> Vec<Placement> {
+        let column_count = cells
+            .iter()
+            .map(|c| (c.column.unwrap_or(0) + c.column_span.max(1)) as usize)
+            .max()
+            .unwrap_or(0)
+            .max(self.columns.len())
+            .max(1);
+        let mut placements: Vec<_> = cells
+            .iter()
+            .map(|cell| Placement {
+                column: cell.column.unwrap_or(0) as usize,
+                row: cell.row.unwrap_or(0) as usize,
+                column_span: cell.column_span.max(1) as usize,
+                row_span: cell.row_span.max(1) as usize,
+            })
+            .collect();
+        let is_auto = |cell: &GridCell| cell.column.is_none() || cell.row.is_none();
+
+        let mut taken: Vec<Vec<bool>> = vec![];
+        for (_, placement) in cells.iter().zip(&placements).filter(|(c, _)| !is_auto(c)) {
+            take(&mut taken, placement);
+        }
+
+        let mut cursor = (0, 0);
+        for (_, placement) in cells
+            .iter()
+            .zip(&mut placements)
+            .filter(|(c, _)| is_auto(c))
+        {
+            let (mut column, mut row) = cursor;
+            loop {
+                if column + placement.column_span > column_count {
+                    column = 0;
+                    row += 1;
+                    continue;
+                }
+                placement.column = column;
+                placement.row = row;
+                if is_free(&taken, placement) {
+                    break;
+                }
+                column += 1;
+            }
+            cursor = (column + placement.column_span, row);
+            take(&mut taken, placement);
+        }
+
+        placements
+    }
+
+    /// How many columns and rows there are, defined or not.
+    pub(super) fn size(&self, placements: &[Placement]) -> (usize, usize) {
+        let columns = placements
+            .iter()
+            .map(|p| p.column + p.column_span)
+            .max()
+            .unwrap_or(0);
+        let rows = placements
+            .iter()
+            .map(|p| p.row + p.row_span)
+            .max()
+            .unwrap_or(0);
+        (columns.max(self.columns.len()), rows.max(self.rows.len()))
+    }
+}
+
+fn take(taken: &mut Vec<Vec<bool>>, p: &Placement) {
+    for row in p.row..(p.row + p.row_span) {
+        if taken.len() <= row {
+            taken.resize(row + 1, vec![]);
+        }
+        let end = p.column + p.column_span;
+        if taken[row].len() < end {
+            taken[row].resize(end, false);
+        }
+        taken[row][p.column..end].fill(true);
+    }
+}
+
+fn is_free(taken: &[Vec<bool>], p: &Placement) -> bool {
+    (p.row..(p.row + p.row_span)).all(|row| {
+        (p.column..(p.column + p.column_span)).all(|column| {
+            !taken
+                .get(row)
+                .is_some_and(|r| r.get(column).is_some_and(|t| *t))
+        })
+    })
+}
+
+impl<W, H> LayoutTemplate<W, H> {
+    /// Where each child goes in this grid, and the lengths of the columns or rows as far as a
+    /// shrink pass can tell. `min_lengths` are the children's lengths along `axis`.
+    pub(super) fn grid_tracks(
+        &self,
+        axis: Axis,
+        cells: &[GridCell],
+        min_lengths: &[u32],
+    ) -> (Vec<Placement>, Vec<ShrunkLength>) {
+        let placements = self.grid.place(cells);
+        let (column_count, row_count) = self.grid.size(&placements);
+        let (definitions, count) = match axis {
+            Axis::Horizontal => (&self.grid.columns, column_count),
+            Axis::Vertical => (&self.grid.rows, row_count),
+        };
+        let children: Vec<_> = placements
+            .iter()
+            .zip(min_lengths)
+            .map(|(p, min)| match axis {
+                Axis::Horizontal => (p.column, p.column_span, *min),
+                Axis::Vertical => (p.row, p.row_span, *min),
+            })
+            .collect();
+        let tracks = shrink_tracks(definitions, count, &children, self.spacing);
+        (placements, tracks)
+    }
+
+    /// The least room the tracks need, spacing included.
+    pub(super) fn grid_needed_length(&self, tracks: &[ShrunkLength]) -> u32 {
+        tracks.iter().map(|t| t.min_length()).sum::<u32>()
+            + self.spacing * tracks.len().saturating_sub(1) as u32
+    }
+}
+
+/// Track lengths as far as a shrink pass can tell, from their definitions and the lengths of
+/// the children in them. `children` are `(first track, span, min length)`.
+///
+/// A child spanning several tracks that don't have room for it widens each of them evenly.
+fn shrink_tracks(
+    definitions: &[Length],
+    count: usize,
+    children: &[(usize, usize, u32)],
+    spacing: u32,
+) -> Vec<ShrunkLength> {
+    let mut content = vec![0; count];
+    for (track, _, min) in children.iter().filter(|(_, span, _)| *span == 1) {
+        content[*track] = content[*track].max(*min);
+    }
+    for (track, span, min) in children.iter().filter(|(_, span, _)| *span > 1) {
+        let spanned = &mut content[*track..(track + span)];
+        let room = spanned.iter().sum::<u32>() + spacing * (*span as u32 - 1);
+        let shares = distribute(min.saturating_sub(room), &vec![1; *span]);
+        spanned
+            .iter_mut()
+            .zip(shares)
+            .for_each(|(length, share)| *length += share);
+    }
+
+    content
+        .into_iter()
+        .enumerate()
+        .map(
+            |(i, content)| match definitions.get(i).copied().unwrap_or_default() {
+                Length::Fixed(l) => ShrunkLength::Fixed(l),
+                Length::Shrink => ShrunkLength::Fixed(content),
+                Length::Grow => ShrunkLength::grow(1, content),
+                Length::FillPortion(portion) => ShrunkLength::grow(portion, content),
+                Length::Percent(p) => ShrunkLength::Percent(p),
+            },
+        )
+        .collect()
+}
+
+/// Where each track starts, relative to the start of the first.
+pub(super) fn track_starts(lengths: &[u32], spacing: u32) -> Vec<u32> {
+    lengths
+        .iter()
+        .scan(0, |start, length| {
+            let this = *start;
+            *start += length + spacing;
+            Some(this)
+        })
+        .collect()
+}
+
+/// Length covered by `span` tracks starting at `track`, including the spacing between them.
+pub(super) fn spanned_length(lengths: &[u32], track: usize, span: usize, spacing: u32) -> u32 {
+    lengths[track..(track + span)].iter().sum::<u32>() + spacing * (span as u32 - 1)
+}
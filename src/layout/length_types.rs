@@ -1,26 +1,119 @@
-#[derive(Clone, Copy, Debug)]
-pub(super) struct FlowCross(pub u32, pub u32);
-#[derive(Clone, Copy, Debug)]
-pub(super) struct XY(pub u32, pub u32);
-
-#[derive(Clone, Copy, Default, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub(super) enum ShrunkLength {
-    #[default]
-    Grow,
-    GrowWithMin(u32),
+    /// Takes `portion` shares of the leftover space, on top of `min`
+    Grow {
+        portion: u32,
+        min: u32,
+    },
+    /// Percentage of the parent's length inside its padding
+    Percent(u32),
     Fixed(u32),
 }
 
+impl ShrunkLength {
+    /// Length known before the leftover space is handed out
+    pub(super) fn min_length(self) -> u32 {
+        match self {
+            ShrunkLength::Grow { min, .. } => min,
+            ShrunkLength::Percent(_) => 0,
+            ShrunkLength::Fixed(l) => l,
+        }
+    }
+
+    /// How many shares of the leftover space this takes
+    pub(super) fn portion(self) -> u32 {
+        match self {
+            ShrunkLength::Grow { portion, .. } => portion,
+            ShrunkLength::Percent(_) | ShrunkLength::Fixed(_) => 0,
+        }
+    }
+
+    /// Final length when there are `available` pixels for this alone, like across a row or
+    /// column, or in a grid cell.
+    pub(super) fn fit(self, available: u32, limits: Limits) -> u32 {
+        match self {
+            ShrunkLength::Grow { min, .. } => limits.clamp(available.max(min)),
+            ShrunkLength::Percent(p) => limits.clamp(percent_of(p, available)),
+            ShrunkLength::Fixed(l) => l,
+        }
+    }
+
+    pub(super) fn grow(portion: u32, min: u32) -> Self {
+        ShrunkLength::Grow { portion, min }
+    }
+}
+
+/// The range a node's length is kept within by its min and max constraints. When the two
+/// disagree the minimum wins.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(super) struct Limits {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Limits {
+    pub(super) fn new(min: Option<u32>, max: Option<u32>) -> Self {
+        let min = min.unwrap_or(0);
+        Self {
+            min,
+            max: max.unwrap_or(u32::MAX).max(min),
+        }
+    }
+
+    pub(super) fn clamp(self, length: u32) -> u32 {
+        length.clamp(self.min, self.max)
+    }
+
+    /// Applies the limits to whatever is already known after a shrink pass. Percentages are
+    /// clamped once the parent's length is known.
+    pub(super) fn apply(self, length: ShrunkLength) -> ShrunkLength {
+        match length {
+            ShrunkLength::Grow { portion, min } => ShrunkLength::grow(portion, self.clamp(min)),
+            ShrunkLength::Percent(p) => ShrunkLength::Percent(p),
+            ShrunkLength::Fixed(l) => ShrunkLength::Fixed(self.clamp(l)),
+        }
+    }
+}
+
 pub(super) type GrownLength = u32;
 
 #[derive(Clone, Copy, Default, Debug)]
 pub enum Length {
+    /// Fills the space left over by its siblings. Same as `FillPortion(1)`.
     Grow,
+    /// Fills its share of the space left over by its siblings, where each sibling gets shares in
+    /// proportion to its portion. `FillPortion(2)` next to `Grow` ends up twice as long.
+    FillPortion(u32),
+    /// Percentage of the parent's length inside its padding.
+    Percent(u32),
     #[default]
     Shrink,
     Fixed(u32),
 }
 
+/// Splits `remaining` between children in proportion to their `portions`. Rounding leftovers
+/// are spread across the children so the shares always add up to exactly `remaining` (unless
+/// every portion is 0).
+pub(super) fn distribute(remaining: u32, portions: &[u32]) -> Vec<u32> {
+    let total: u64 = portions.iter().map(|p| *p as u64).sum();
+    if total == 0 {
+        return vec![0; portions.len()];
+    }
+    portions
+        .iter()
+        .scan(0, |cumulative, portion| {
+            let before = remaining as u64 * *cumulative / total;
+            *cumulative += *portion as u64;
+            let after = remaining as u64 * *cumulative / total;
+            Some((after - before) as u32)
+        })
+        .collect()
+}
+
+pub(super) fn percent_of(percent: u32, length: u32) -> u32 {
+    (length as u64 * percent as u64 / 100) as u32
+}
+
 impl From<u32> for Length {
     fn from(value: u32) -> Self {
         Length::Fixed(value)
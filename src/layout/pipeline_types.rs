@@ -1,17 +1,38 @@
+use std::ops::Range;
+
 use super::{
-    length_types::{FlowCross, GrownLength, Length, ShrunkLength},
+    grid::{Grid, GridCell},
+    length_types::{distribute, percent_of, GrownLength, Length, Limits, ShrunkLength},
     Padding,
 };
 
-#[derive(Clone, Copy, Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct LayoutTemplate<Width, Height> {
     // about the node itself
     pub width: Width,
     pub height: Height,
+    pub min_width: Option<u32>,
+    pub max_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_height: Option<u32>,
+    /// Where this node goes when its parent is a grid
+    pub cell: GridCell,
+    /// Where this node goes when its parent is a stack, instead of following the stack's
+    /// `justify` and `align`
+    pub anchor: Option<Anchor>,
+    /// How far right and down of its anchor this node goes
+    pub offset: (i32, i32),
     // about its children
     pub padding: Padding,
     pub direction: LayoutDirection,
     pub spacing: u32,
+    pub justify: Justify,
+    pub align: Align,
+    /// Children keep their natural height and may run past the bottom of this node, to be
+    /// scrolled into view. See [`Scrollable`](crate::scrollable::Scrollable).
+    pub scroll: bool,
+    /// Tracks children are placed in when `direction` is [`LayoutDirection::Grid`]
+    pub grid: Grid,
 }
 
 #[derive(Clone, Copy, Default, PartialEq, Debug)]
@@ -20,6 +41,89 @@ pub enum LayoutDirection {
     #[default]
     Row,
     Stack,
+    /// Children go in the cells of the node's [`Grid`], and can span several of them. Inside
+    /// its cell a child is placed by `justify` horizontally and `align` vertically, like in a
+    /// stack.
+    Grid,
+    /// Like a row, but children that don't fit on the current line move onto a new one below
+    /// it. Each line is justified on its own, and children are aligned within their line.
+    Wrap,
+}
+
+/// Where children go along the direction they flow in, when they don't fill it.
+///
+/// Stacks don't flow, so for them this is horizontal alignment, with each child placed on its
+/// own.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Justify {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Leftover space goes between the children, none before the first or after the last.
+    SpaceBetween,
+    /// Leftover space is split evenly around each child, so the outer gaps are half the
+    /// inner ones.
+    SpaceAround,
+}
+
+/// Where children go across the direction they flow in. For stacks this is vertical.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Align {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Children with a [`Length::Shrink`] cross length grow to fill the parent.
+    Stretch,
+}
+
+/// A point of a stack, inside its padding, that a child can be pinned to. The child's matching
+/// point goes there: a child anchored to `BottomRight` has its bottom right corner in the
+/// stack's bottom right corner.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Top left corner of a child of `size` anchored here in `area`, moved by `offset`. Stops at
+    /// the top and left edges of the screen.
+    pub(super) fn position(
+        self,
+        area: CalculatedLayout,
+        size: (u32, u32),
+        offset: (i32, i32),
+    ) -> (u32, u32) {
+        // how far along each axis the anchor is, in halves
+        let (across, down) = match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (1, 0),
+            Anchor::TopRight => (2, 0),
+            Anchor::Left => (0, 1),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (2, 1),
+            Anchor::BottomLeft => (0, 2),
+            Anchor::Bottom => (1, 2),
+            Anchor::BottomRight => (2, 2),
+        };
+        let place = |start: u32, available: u32, length: u32, halves: i64, offset: i32| {
+            let free = available as i64 - length as i64;
+            (start as i64 + free * halves / 2 + offset as i64).max(0) as u32
+        };
+        (
+            place(area.x, area.w, size.0, across, offset.0),
+            place(area.y, area.h, size.1, down, offset.1),
+        )
+    }
 }
 
 pub type Layout = LayoutTemplate<Length, Length>;
@@ -29,17 +133,205 @@ pub type ShrinkHeightLayout = LayoutTemplate<GrownLength, ShrunkLength>;
 pub type GrownHeightLayout = LayoutTemplate<GrownLength, GrownLength>;
 
 impl<W, H> LayoutTemplate<W, H> {
-    pub(super) fn summed_padding(&self) -> FlowCross {
-        let width = self.padding.left + self.padding.right;
-        let height = self.padding.top + self.padding.bottom;
-        match self.direction {
-            LayoutDirection::Column => FlowCross(height, width),
-            LayoutDirection::Row | LayoutDirection::Stack => FlowCross(width, height),
+    pub(super) fn width_limits(&self) -> Limits {
+        Limits::new(self.min_width, self.max_width)
+    }
+
+    pub(super) fn height_limits(&self) -> Limits {
+        Limits::new(self.min_height, self.max_height)
+    }
+
+    /// The least room `children` can be laid out in without overflowing, either one after
+    /// another along the flow direction or side by side across it.
+    pub(super) fn needed_length(&self, children: &[(ShrunkLength, Limits)], flows: bool) -> u32 {
+        let min_lengths = children.iter().map(|(c, _)| c.min_length());
+        if flows {
+            min_lengths.sum::<u32>() + self.spacing * children.len().saturating_sub(1) as u32
+        } else {
+            min_lengths.max().unwrap_or_default()
+        }
+    }
+
+    /// Final lengths of children along the direction they flow in, given the length available
+    /// inside the padding.
+    ///
+    /// Percentages are taken of `available`, then whatever is left after spacing and every
+    /// child's minimum is split between the growing children. A growing child that would end up
+    /// past its maximum is held there and the split is redone without it, so its siblings get
+    /// the space it couldn't take.
+    pub(super) fn flow_lengths(
+        &self,
+        children: &[(ShrunkLength, Limits)],
+        available: u32,
+    ) -> Vec<u32> {
+        // `Some` once a child's length is settled
+        let mut lengths: Vec<_> = children
+            .iter()
+            .map(|(c, limits)| match c {
+                ShrunkLength::Grow { .. } => None,
+                ShrunkLength::Percent(p) => Some(limits.clamp(percent_of(*p, available))),
+                ShrunkLength::Fixed(l) => Some(*l),
+            })
+            .collect();
+        let spacing = self.spacing * children.len().saturating_sub(1) as u32;
+
+        loop {
+            let taken: u32 = children
+                .iter()
+                .zip(&lengths)
+                .map(|((c, _), length)| length.unwrap_or(c.min_length()))
+                .sum();
+            let remaining_length = available.saturating_sub(taken).saturating_sub(spacing);
+            let portions: Vec<_> = children
+                .iter()
+                .zip(&lengths)
+                .map(|((c, _), length)| match length {
+                    Some(_) => 0,
+                    None => c.portion(),
+                })
+                .collect();
+            let shares = distribute(remaining_length, &portions);
+
+            let mut clamped = false;
+            for (((c, limits), length), share) in children.iter().zip(&mut lengths).zip(&shares) {
+                if length.is_none() && c.min_length() + share > limits.max {
+                    *length = Some(limits.max);
+                    clamped = true;
+                }
+            }
+            if !clamped {
+                return children
+                    .iter()
+                    .zip(lengths)
+                    .zip(shares)
+                    .map(|(((c, _), length), share)| length.unwrap_or(c.min_length() + share))
+                    .collect();
+            }
+        }
+    }
+
+    /// Splits children with these `(width, height)`s into lines no wider than `available_width`,
+    /// each with the height of its tallest child. A child too wide for any line gets one of its
+    /// own.
+    pub(super) fn wrap_lines(
+        &self,
+        sizes: &[(u32, u32)],
+        available_width: u32,
+    ) -> Vec<(Range<usize>, u32)> {
+        let mut lines = vec![];
+        let mut start = 0;
+        let mut line_width = 0;
+        for (i, (width, _)) in sizes.iter().enumerate() {
+            if i > start && line_width + self.spacing + width > available_width {
+                lines.push(start..i);
+                start = i;
+            }
+            line_width = match i == start {
+                true => *width,
+                false => line_width + self.spacing + width,
+            };
+        }
+        if start < sizes.len() {
+            lines.push(start..sizes.len());
+        }
+        lines
+            .into_iter()
+            .map(|line| {
+                let height = sizes[line.clone()].iter().map(|(_, h)| *h).max();
+                (line, height.unwrap_or_default())
+            })
+            .collect()
+    }
+
+    /// Height of these lines stacked on top of each other, spacing included.
+    pub(super) fn wrap_needed_height(&self, lines: &[(Range<usize>, u32)]) -> u32 {
+        lines.iter().map(|(_, h)| h).sum::<u32>()
+            + self.spacing * lines.len().saturating_sub(1) as u32
+    }
+
+    /// Offsets of children with these sizes from the first child's position, once they are
+    /// wrapped into lines inside `available_width`.
+    pub(super) fn wrap_offsets(
+        &self,
+        sizes: &[(u32, u32)],
+        available_width: u32,
+    ) -> Vec<(u32, u32)> {
+        let mut line_y = 0;
+        self.wrap_lines(sizes, available_width)
+            .into_iter()
+            .flat_map(|(line, line_height)| {
+                let line_sizes = &sizes[line];
+                let count = line_sizes.len() as u32;
+                let free = available_width.saturating_sub(
+                    line_sizes.iter().map(|(w, _)| *w).sum::<u32>()
+                        + self.spacing * count.saturating_sub(1),
+                );
+                let y = line_y;
+                line_y += line_height + self.spacing;
+                line_sizes
+                    .iter()
+                    .enumerate()
+                    .scan(0, move |x, (i, (w, h))| {
+                        let offset = (
+                            *x + self.justify.offset(free, i as u32, count),
+                            y + self.align.offset(line_height.saturating_sub(*h)),
+                        );
+                        *x += w + self.spacing;
+                        Some(offset)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Whether a child with this anchor floats over this node's other children, rather than
+    /// taking up room among them.
+    pub(super) fn floats(&self, anchor: Option<Anchor>) -> bool {
+        self.direction == LayoutDirection::Stack && anchor.is_some()
+    }
+
+    /// Whether shrinking children should fill this node horizontally.
+    pub(super) fn stretches_width(&self) -> bool {
+        self.align == Align::Stretch && self.direction == LayoutDirection::Column
+    }
+
+    /// Whether shrinking children should fill this node vertically.
+    pub(super) fn stretches_height(&self) -> bool {
+        self.align == Align::Stretch
+            && matches!(
+                self.direction,
+                LayoutDirection::Row | LayoutDirection::Stack | LayoutDirection::Wrap
+            )
+    }
+}
+
+impl Justify {
+    /// How far child `index` of `count` is pushed along, given `free` pixels of leftover space.
+    /// Computed per child so no rounding error accumulates.
+    pub(super) fn offset(self, free: u32, index: u32, count: u32) -> u32 {
+        match self {
+            Justify::Start => 0,
+            Justify::Center => free / 2,
+            Justify::End => free,
+            Justify::SpaceBetween if count > 1 => free * index / (count - 1),
+            Justify::SpaceBetween => 0,
+            Justify::SpaceAround => free * (2 * index + 1) / (2 * count),
+        }
+    }
+}
+
+impl Align {
+    /// How far a child is pushed across, given `free` pixels of leftover space.
+    pub(super) fn offset(self, free: u32) -> u32 {
+        match self {
+            Align::Start | Align::Stretch => 0,
+            Align::Center => free / 2,
+            Align::End => free,
         }
     }
 }
 
-#[derive(Clone, Copy, Default, Debug)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
 pub struct CalculatedLayout {
     pub x: u32,
     pub y: u32,
@@ -4,43 +4,138 @@ use error_iter::ErrorIter as _;
 use log::error;
 use pixels::{wgpu, Error, Pixels, SurfaceTexture};
 use winit::{
-    dpi::LogicalSize,
-    event::{Event, WindowEvent},
+    dpi::{LogicalSize, PhysicalSize},
+    event::{ElementState, Event, KeyEvent, MouseScrollDelta, WindowEvent},
     event_loop::EventLoop,
-    keyboard::KeyCode,
+    keyboard::PhysicalKey,
     window::WindowBuilder,
 };
 use winit_input_helper::WinitInputHelper;
 
-use crate::{constants::SCALE, layout::Layout, palette::Color, state_tree::StateNode, tree::Node};
+use crate::{
+    constants::{SCALE, WHEEL_LINE},
+    modal::Layers,
+    palette::Color,
+    runtime::Runtime,
+};
 
 pub mod buffer;
 pub mod button;
 pub mod constants;
 pub mod element;
 pub mod font;
+pub mod headless;
 pub mod image;
+pub mod key_binding;
 pub mod layout;
+pub mod modal;
 pub mod mouse_area;
+pub mod nine_slice;
 pub mod palette;
 pub mod quad;
+mod runtime;
+pub mod scrollable;
 pub mod slider;
+pub mod sprite;
 pub mod state_tree;
 pub mod text;
+pub mod text_input;
 pub mod tree;
 pub mod widget;
 
+pub use winit::keyboard::KeyCode;
+
 /// Holds all of the current frame's input state
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Input {
     pub mouse_released: bool,
     pub mouse_right_released: bool,
     pub mouse_down: bool,
     pub mouse_pos: Option<(u32, u32)>,
     pub prev_mouse_pos: Option<(u32, u32)>,
+    /// How far the mouse wheel moved this frame, in pixels. Positive `y` is the wheel turning
+    /// away from the user, which scrolls toward the top of the content.
+    pub wheel_delta: (i32, i32),
+    /// Keys that went down this frame
+    pub keys_pressed: Vec<KeyCode>,
+    /// Keys the OS sent a repeat for this frame, because they are being held down
+    pub keys_repeated: Vec<KeyCode>,
+    /// Keys that went up this frame
+    pub keys_released: Vec<KeyCode>,
+    /// Keys that are currently down, including the ones pressed this frame
+    pub keys_held: Vec<KeyCode>,
+    pub modifiers: Modifiers,
+    /// Text typed this frame, after the keyboard layout is applied. Control characters (like the
+    /// ones sent for Backspace and Enter) are left out.
+    pub text: String,
+}
+
+impl Input {
+    pub fn key_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// Pressed this frame, or repeated by the OS because it is being held down
+    pub fn key_pressed_or_repeated(&self, key: KeyCode) -> bool {
+        self.key_pressed(key) || self.keys_repeated.contains(&key)
+    }
+
+    pub fn key_released(&self, key: KeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    pub fn key_held(&self, key: KeyCode) -> bool {
+        self.keys_held.contains(&key)
+    }
+}
+
+/// Which modifier keys are held down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self {
+        shift: false,
+        control: false,
+        alt: false,
+    };
+    pub const SHIFT: Self = Self {
+        shift: true,
+        ..Self::NONE
+    };
+    pub const CONTROL: Self = Self {
+        control: true,
+        ..Self::NONE
+    };
+    pub const ALT: Self = Self {
+        alt: true,
+        ..Self::NONE
+    };
 }
 
-pub fn run<'a, State, Message, Update, View, Timer>(
+/// Settings for [`run_with_options`] that most apps can leave alone.
+#[derive(Debug, Clone, Copy)]
+pub struct RunOptions {
+    /// Close the window when Escape is pressed, unless a modal is open or an element like a text
+    /// input is focused. Turn this off to use Escape in your own key bindings.
+    pub quit_on_escape: bool,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            quit_on_escape: true,
+        }
+    }
+}
+
+/// Opens a window and runs the app in it until the window is closed or Escape is pressed. Use
+/// [`run_with_options`] to keep Escape for the app.
+pub fn run<'a, State, Message, Update, View, Screen, Timer>(
     initial_state: State,
     update: Update,
     view: View,
@@ -52,12 +147,52 @@ pub fn run<'a, State, Message, Update, View, Timer>(
 //TODO: make a custom error type
 where
     Update: Fn(Message, &mut State),
-    View: Fn(&State) -> Node<'a, Message, Layout>,
+    View: Fn(&State) -> Screen,
+    Screen: Into<Layers<'a, Message>>,
+    Timer: Fn(Duration) -> Option<Message>,
+{
+    run_with_options(
+        initial_state,
+        update,
+        view,
+        width,
+        height,
+        clear_color,
+        timer,
+        RunOptions::default(),
+    )
+}
+
+/// Like [`run`], with [`RunOptions`] to change how the window behaves.
+#[allow(clippy::too_many_arguments)]
+pub fn run_with_options<'a, State, Message, Update, View, Screen, Timer>(
+    initial_state: State,
+    update: Update,
+    view: View,
+    width: u32,
+    height: u32,
+    clear_color: Color,
+    timer: Timer, // TODO turn this into an optional thing / run into a struct
+    options: RunOptions,
+) -> Result<(), Error>
+//TODO: make a custom error type
+where
+    Update: Fn(Message, &mut State),
+    View: Fn(&State) -> Screen,
+    Screen: Into<Layers<'a, Message>>,
     Timer: Fn(Duration) -> Option<Message>,
 {
     env_logger::init();
 
-    let mut state = initial_state;
+    let mut runtime = Runtime::new(
+        initial_state,
+        update,
+        view,
+        width,
+        height,
+        clear_color,
+        timer,
+    );
 
     let mut old_input: Option<Input> = None;
 
@@ -87,13 +222,14 @@ where
     let srgb = to_linear_rgb(clear_color);
     pixels.clear_color(srgb);
 
-    let mut new_root = Node::root_node(width as usize, height as usize);
-    new_root.push(view(&state)); // TODO need to handle layout changes with diffing (see Iced)
-    let mut state_root = StateNode::new(&new_root);
-    let mut root = new_root.calculate_layout();
-
     let mut mouse_position: Result<(usize, usize), (isize, isize)> = Err((0, 0));
 
+    // winit_input_helper can only be asked about specific keys, so we remember every key it has
+    // seen an event for and ask about those
+    let mut seen_keys: Vec<KeyCode> = vec![];
+    let mut typed_text = String::new();
+    let mut wheel_delta = (0.0, 0.0);
+
     let res = event_loop.run(|event, elwt| {
         // TODO: consider only calculating when necessary
         // Draw the current frame
@@ -102,11 +238,7 @@ where
             ..
         } = event
         {
-            // Clear
-            for pixel in pixels.frame_mut().chunks_exact_mut(4) {
-                pixel.copy_from_slice(&clear_color);
-            }
-            root.draw_recursive(pixels.frame_mut(), (0, 0));
+            runtime.draw(pixels.frame_mut());
 
             if let Err(err) = pixels.render() {
                 log_error("pixels.render", err);
@@ -128,10 +260,54 @@ where
             mouse_position = pixels.window_pos_to_pixel(position.into());
         }
 
+        if let Event::WindowEvent {
+            event:
+                WindowEvent::KeyboardInput {
+                    event:
+                        KeyEvent {
+                            physical_key: PhysicalKey::Code(key),
+                            state,
+                            ref text,
+                            ..
+                        },
+                    ..
+                },
+            ..
+        } = event
+        {
+            if let (ElementState::Pressed, Some(text)) = (state, text) {
+                typed_text.extend(text.chars().filter(|c| !c.is_control()));
+            }
+            if !seen_keys.contains(&key) {
+                seen_keys.push(key);
+            }
+        }
+
+        if let Event::WindowEvent {
+            event: WindowEvent::MouseWheel { delta, .. },
+            ..
+        } = event
+        {
+            let (x, y) = match delta {
+                MouseScrollDelta::LineDelta(x, y) => {
+                    (x as f64 * WHEEL_LINE as f64, y as f64 * WHEEL_LINE as f64)
+                }
+                MouseScrollDelta::PixelDelta(position) => {
+                    window_to_frame_distance(&pixels, window.inner_size(), (position.x, position.y))
+                }
+            };
+            wheel_delta.0 += x;
+            wheel_delta.1 += y;
+        }
+
         // Handle input events
         if winit_input.update(&event) {
             // Close events
-            if winit_input.key_pressed(KeyCode::Escape) || winit_input.close_requested() {
+            if winit_input.close_requested()
+                || (options.quit_on_escape
+                    && winit_input.key_pressed(KeyCode::Escape)
+                    && !runtime.takes_escape())
+            {
                 elwt.exit();
                 return;
             }
@@ -157,12 +333,39 @@ where
                 None => None,
             };
 
+            let keys_where = |query: fn(&WinitInputHelper, KeyCode) -> bool| -> Vec<KeyCode> {
+                seen_keys
+                    .iter()
+                    .copied()
+                    .filter(|key| query(&winit_input, *key))
+                    .collect()
+            };
+
             let input = Input {
                 mouse_released: winit_input.mouse_released(0),
                 mouse_right_released: winit_input.mouse_released(1),
                 mouse_pos: input_mouse_pos,
                 prev_mouse_pos,
+                wheel_delta: {
+                    // hand over whole pixels only, so slow scrolling adds up over the frames
+                    let (x, y) = (wheel_delta.0.round(), wheel_delta.1.round());
+                    wheel_delta.0 -= x;
+                    wheel_delta.1 -= y;
+                    (x as i32, y as i32)
+                },
                 mouse_down: winit_input.mouse_held(0),
+                keys_pressed: keys_where(WinitInputHelper::key_pressed),
+                keys_repeated: keys_where(|input, key| {
+                    input.key_pressed_os(key) && !input.key_pressed(key)
+                }),
+                keys_released: keys_where(WinitInputHelper::key_released),
+                keys_held: keys_where(WinitInputHelper::key_held),
+                modifiers: Modifiers {
+                    shift: winit_input.held_shift(),
+                    control: winit_input.held_control(),
+                    alt: winit_input.held_alt(),
+                },
+                text: std::mem::take(&mut typed_text),
             };
 
             // Handle Messages
@@ -170,23 +373,7 @@ where
             let d = now - time_of_last_timer;
             time_of_last_timer = now;
 
-            let messages: Vec<_> = [root.get_message(&mut state_root, &input), timer(d)]
-                .into_iter()
-                // Filters None values
-                .flatten()
-                .collect();
-
-            let update_needed = !messages.is_empty();
-
-            messages
-                .into_iter()
-                .for_each(|message| update(message, &mut state));
-
-            if update_needed {
-                let mut new_root = Node::root_node(width as usize, height as usize);
-                new_root.push(view(&state));
-                root = new_root.calculate_layout();
-            }
+            runtime.handle_input(&input, d);
 
             // Update internal state and request a redraw
             //            world.update();
@@ -197,6 +384,26 @@ where
     res.map_err(|e| Error::UserDefined(Box::new(e)))
 }
 
+/// Converts a distance in physical window pixels to frame pixels, with the same mapping `pixels`
+/// uses for the mouse position, so it stays right when the window is resized.
+fn window_to_frame_distance(
+    pixels: &Pixels,
+    window_size: PhysicalSize<u32>,
+    (x, y): (f64, f64),
+) -> (f64, f64) {
+    // positions outside the frame come back as errors, but are mapped all the same
+    let to_frame = |position| match pixels.window_pos_to_pixel(position) {
+        Ok((x, y)) => (x as f64, y as f64),
+        Err((x, y)) => (x as f64, y as f64),
+    };
+    let (left, top) = to_frame((0.0, 0.0));
+    let (right, bottom) = to_frame((window_size.width as f32, window_size.height as f32));
+    (
+        x * (right - left) / window_size.width.max(1) as f64,
+        y * (bottom - top) / window_size.height.max(1) as f64,
+    )
+}
+
 fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
     error!("{method_name}() failed: {err}");
     for source in err.sources().skip(1) {
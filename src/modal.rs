@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use crate::{
+    buffer::Buffer,
+    layout::{Align, CalculatedLayout, Justify, Layout, LayoutError},
+    palette::{Color, MAIN_DARK},
+    state_tree::StateNode,
+    tree::Node,
+    Input, KeyCode,
+};
+
+/// Everything a view puts on screen: the app itself, and maybe a [`Modal`] on top of it.
+///
+/// Views can return a plain [`Node`] when they never open a modal.
+pub struct Layers<'a, Message> {
+    pub main: Node<'a, Message, Layout>,
+    pub modal: Option<Modal<'a, Message>>,
+}
+
+impl<'a, Message> Layers<'a, Message> {
+    pub fn new(main: impl Into<Node<'a, Message, Layout>>) -> Self {
+        Self {
+            main: main.into(),
+            modal: None,
+        }
+    }
+
+    /// Opens `modal` over the main view.
+    pub fn modal(self, modal: Modal<'a, Message>) -> Self {
+        Self {
+            modal: Some(modal),
+            ..self
+        }
+    }
+}
+
+impl<'a, Message> From<Node<'a, Message, Layout>> for Layers<'a, Message> {
+    fn from(main: Node<'a, Message, Layout>) -> Self {
+        Self::new(main)
+    }
+}
+
+/// A dialog the runtime centers over the whole app.
+///
+/// While a modal is open it gets all of the input: nothing underneath can be clicked or typed
+/// into. The main view is dimmed by covering every other pixel with the dim color. Clicking
+/// outside the dialog or pressing Escape sends the dismiss message, and the modal stays open
+/// until the view stops returning it.
+pub struct Modal<'a, Message> {
+    content: Node<'a, Message, Layout>,
+    dim_color: Color,
+    on_dismiss: Option<Box<dyn Fn() -> Message>>,
+}
+
+impl<'a, Message> Modal<'a, Message> {
+    pub fn new(content: impl Into<Node<'a, Message, Layout>>) -> Self {
+        Self {
+            content: content.into(),
+            dim_color: MAIN_DARK,
+            on_dismiss: None,
+        }
+    }
+
+    pub fn dim_color(mut self, color: Color) -> Self {
+        self.dim_color = color;
+        self
+    }
+
+    pub fn on_dismiss<F>(mut self, m: F) -> Self
+    where
+        F: Fn() -> Message + 'static,
+    {
+        self.on_dismiss = Some(Box::new(m));
+        self
+    }
+}
+
+/// A [`Modal`] the runtime has laid out, along with its widget state.
+pub(crate) struct ModalLayer<'a, Message> {
+    root: Node<'a, Message, CalculatedLayout>,
+    pub state: StateNode,
+    dim_color: Color,
+    on_dismiss: Option<Box<dyn Fn() -> Message>>,
+}
+
+impl<'a, Message> ModalLayer<'a, Message> {
+    /// Lays `modal` out over a screen of `width` by `height`, keeping the widget state of the
+    /// modal that was open before, if any. Anything that didn't fit goes in `layout_errors`.
+    pub fn new(
+        modal: Modal<'a, Message>,
+        width: u32,
+        height: u32,
+        previous: Option<StateNode>,
+        layout_errors: &mut Vec<LayoutError>,
+    ) -> Self {
+        let mut root = Node::root_node(width as usize, height as usize)
+            .stack()
+            .justify(Justify::Center)
+            .align(Align::Center);
+        root.push(modal.content);
+        let state = match previous {
+            Some(mut state) => {
+                state.diff(&root);
+                state
+            }
+            None => StateNode::new(&root),
+        };
+        Self {
+            root: root.calculate_layout_reporting(layout_errors),
+            state,
+            dim_color: modal.dim_color,
+            on_dismiss: modal.on_dismiss,
+        }
+    }
+
+    pub fn get_message(&mut self, input: &Input) -> Option<Message> {
+        let message = self.root.get_message(&mut self.state, input);
+        if message.is_some() {
+            return message;
+        }
+        let dialog = self.root.children[0].layout;
+        let clicked_outside =
+            input.mouse_released && input.mouse_pos.is_some_and(|pos| !dialog.contains(pos));
+        if clicked_outside || input.key_pressed(KeyCode::Escape) {
+            return self.on_dismiss.as_ref().map(|m| m());
+        }
+        None
+    }
+
+    pub fn tick(&mut self, elapsed: Duration, messages: &mut Vec<Message>) {
+        self.root.tick(&mut self.state, elapsed, messages);
+    }
+
+    pub fn draw(&self, frame: &mut Buffer) {
+        frame.dither(self.dim_color);
+        self.root.draw_recursive(frame, &self.state, (0, 0));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    buffer::Buffer,
     element::Element,
     layout::{CalculatedLayout, Layout},
     state_tree::StateNode,
@@ -74,7 +75,7 @@ impl<Message> MouseArea<Message> {
 }
 
 impl<Message> Element<Message> for MouseArea<Message> {
-    fn draw(&self, _frame: &mut [u8], _region: CalculatedLayout) {}
+    fn draw(&self, _frame: &mut Buffer, _tree: &StateNode, _region: CalculatedLayout) {}
 
     fn get_message(
         &mut self,
@@ -128,6 +129,10 @@ impl<Message> Element<Message> for MouseArea<Message> {
         }
         return None;
     }
+
+    fn hit_test(&self, _tree: &StateNode, region: CalculatedLayout, pos: (u32, u32)) -> bool {
+        region.contains(pos)
+    }
 }
 
 impl<'a, Message: 'static> Into<Node<'a, Message, Layout>> for MouseArea<Message> {
@@ -0,0 +1,151 @@
+use crate::{
+    buffer::Buffer,
+    element::Element,
+    image::Image,
+    layout::{CalculatedLayout, Layout, Padding},
+    palette::Color,
+    state_tree::StateNode,
+    tree::Node,
+    Input,
+};
+
+/// How the edges and center of a [`NineSlice`] cover more room than the image has.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum SliceFill {
+    /// Repeats them, which keeps pixel-art patterns crisp
+    #[default]
+    Tile,
+    /// Stretches them, repeating or dropping whole pixels
+    Stretch,
+}
+
+/// A frame drawn from an image cut into nine pieces by four insets. The corners are drawn as
+/// they are, the edges and center are tiled or stretched to fill whatever size layout gives it.
+///
+/// Turned into a [`Node`], it pads its children by the insets so they sit inside the frame, which
+/// also keeps a shrinking frame from getting smaller than its corners.
+#[derive(Clone)]
+pub struct NineSlice {
+    image: Image<Color>,
+    /// How far in from each side of the image the edges end, before scaling
+    insets: Padding,
+    fill: SliceFill,
+}
+
+impl NineSlice {
+    /// The image's scale factor scales the corners and edges up along with it.
+    pub fn new(image: Image<Color>, insets: impl Into<Padding>) -> Self {
+        let insets = insets.into();
+        assert!(
+            insets.left + insets.right <= image.width as u32
+                && insets.top + insets.bottom <= image.height as u32,
+            "nine-slice insets overlap"
+        );
+        Self {
+            image,
+            insets,
+            fill: SliceFill::default(),
+        }
+    }
+
+    pub fn fill(mut self, fill: SliceFill) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Insets in screen pixels
+    fn scaled_insets(&self) -> Padding {
+        let scale = self.image.scale_factor as u32;
+        Padding {
+            top: self.insets.top * scale,
+            right: self.insets.right * scale,
+            bottom: self.insets.bottom * scale,
+            left: self.insets.left * scale,
+        }
+    }
+
+    /// Which scaled image pixel along one axis ends up `offset` pixels into a region `length`
+    /// long. `None` in the middle when the image has no middle to draw from.
+    fn source(
+        &self,
+        offset: u32,
+        length: u32,
+        image_length: u32,
+        (start, end): (u32, u32),
+    ) -> Option<u32> {
+        let image_middle = image_length - start - end;
+        // when the region is too small for both corners, the start one wins
+        let start = start.min(length);
+        let end = end.min(length - start);
+        let middle = length - start - end;
+        if offset < start {
+            Some(offset)
+        } else if offset >= start + middle {
+            Some(image_length - (length - offset))
+        } else if image_middle == 0 {
+            None
+        } else {
+            let into_middle = offset - start;
+            Some(
+                start
+                    + match self.fill {
+                        SliceFill::Tile => into_middle % image_middle,
+                        SliceFill::Stretch => {
+                            (into_middle as u64 * image_middle as u64 / middle as u64) as u32
+                        }
+                    },
+            )
+        }
+    }
+}
+
+impl<Message> Element<Message> for NineSlice {
+    fn draw(&self, frame: &mut Buffer, _tree: &StateNode, region: CalculatedLayout) {
+        let scale = self.image.scale_factor as u32;
+        let insets = self.scaled_insets();
+        let (width, height) = (
+            self.image.width as u32 * scale,
+            self.image.height as u32 * scale,
+        );
+        // only visit the pixels that can actually be drawn
+        let visible = region.intersection(&frame.clip());
+
+        for y in visible.y..(visible.y + visible.h) {
+            let Some(source_y) =
+                self.source(y - region.y, region.h, height, (insets.top, insets.bottom))
+            else {
+                continue;
+            };
+            for x in visible.x..(visible.x + visible.w) {
+                let Some(source_x) =
+                    self.source(x - region.x, region.w, width, (insets.left, insets.right))
+                else {
+                    continue;
+                };
+                let i =
+                    (source_x / scale) as usize + (source_y / scale) as usize * self.image.width;
+                frame.set_pixel(x as usize, y as usize, self.image.data[i]);
+            }
+        }
+    }
+
+    fn get_message(
+        &mut self,
+        _tree: &mut StateNode,
+        _input: &Input,
+        _region: CalculatedLayout,
+    ) -> Option<Message> {
+        None
+    }
+
+    fn hit_test(&self, _tree: &StateNode, region: CalculatedLayout, pos: (u32, u32)) -> bool {
+        region.contains(pos)
+    }
+}
+
+impl<'a, Message> From<NineSlice> for Node<'a, Message, Layout> {
+    fn from(value: NineSlice) -> Self {
+        let padding = value.scaled_insets();
+        Node::new(value).padding(padding)
+    }
+}
@@ -9,6 +9,55 @@ pub const BLUE_LIGHT: Color = [0x00, 0xb0, 0xbe, 0xff];
 pub const RED_LIGHT: Color = [0xff, 0xb0, 0xa3, 0xff];
 pub const RED_DARK: Color = [0xff, 0x69, 0x73, 0xff];
 
+/// Every color of the palette, in the order of [`color_from_index`].
+pub const PALETTE: [Color; 6] = [
+    MAIN_DARK, MAIN_LIGHT, BLUE_DARK, BLUE_LIGHT, RED_DARK, RED_LIGHT,
+];
+
+/// Index of the palette color closest to `color`, ignoring alpha.
+pub fn nearest_index(color: Color) -> usize {
+    let distance = |other: &Color| -> u32 {
+        (0..3)
+            .map(|i| (color[i] as i32 - other[i] as i32).pow(2) as u32)
+            .sum()
+    };
+    PALETTE
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, c)| distance(c))
+        .map(|(i, _)| i)
+        .unwrap_or_default()
+}
+
+/// `above` drawn over `below`, letting `below` show through as much as `above` is transparent.
+pub fn blend(below: Color, above: Color) -> Color {
+    match above[3] {
+        255 => return above,
+        0 => return below,
+        _ => {}
+    }
+    let above_alpha = above[3] as u32;
+    let below_alpha = below[3] as u32 * (255 - above_alpha);
+    // alpha of the result, times 255
+    let alpha = above_alpha * 255 + below_alpha;
+    let channel = |i: usize| {
+        ((above[i] as u32 * above_alpha * 255 + below[i] as u32 * below_alpha + alpha / 2) / alpha)
+            as u8
+    };
+    [
+        channel(0),
+        channel(1),
+        channel(2),
+        ((alpha + 127) / 255) as u8,
+    ]
+}
+
+/// `color` with its alpha scaled by `coverage`, where 255 leaves it as it is.
+pub fn with_coverage(color: Color, coverage: u8) -> Color {
+    let alpha = (color[3] as u32 * coverage as u32 + 127) / 255;
+    [color[0], color[1], color[2], alpha as u8]
+}
+
 pub fn color_from_index(index: usize) -> Color {
     match index {
         0 => MAIN_DARK,
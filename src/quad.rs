@@ -5,13 +5,12 @@
 * our_quad.fill(red).border_color(blue)
 */
 
+use crate::buffer::Buffer;
 use crate::element::Element;
 use crate::layout::CalculatedLayout;
 use crate::palette::Color;
 use crate::state_tree::StateNode;
 
-const WIDTH: u32 = 320; // TODO make this metadata for the frame buffer
-
 #[derive(Clone, Default)]
 pub struct QuadStyle {
     pub fill_style: Option<Color>,
@@ -83,41 +82,38 @@ impl Quad {
     }
 }
 
-impl<Message> Element<Message> for Quad {
-    fn draw(&self, frame: &mut [u8], region: CalculatedLayout) {
+impl Quad {
+    /// The color drawn at `(x, y)` inside `region`, if any
+    fn color_at(&self, region: CalculatedLayout, (x, y): (u32, u32)) -> Option<Color> {
         let position = (region.x, region.y); // TODO fix types mess
-                                             //TODO: Consider optimizing this if it is a bottleneck
-        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            let x = i as u32 % WIDTH;
-            let y = i as u32 / WIDTH;
-
-            let inside_the_box = x >= position.0
-                && x < position.0 + region.w
-                && y >= position.1
-                && y < position.1 + region.h;
-
-            let rgba = if inside_the_box {
-                match &self.style.border_style {
-                    Some(border_style) => {
-                        let border_thickness = border_style.thickness;
-                        if x < position.0 + border_thickness
-                            || x >= position.0 + region.w - border_thickness
-                            || y < position.1 + border_thickness
-                            || y >= position.1 + region.h - border_thickness
-                        {
-                            Some(border_style.color)
-                        } else {
-                            self.style.fill_style
-                        }
-                    }
-                    None => self.style.fill_style,
+        match &self.style.border_style {
+            Some(border_style) => {
+                let border_thickness = border_style.thickness;
+                if x < position.0 + border_thickness
+                    || x >= (position.0 + region.w).saturating_sub(border_thickness)
+                    || y < position.1 + border_thickness
+                    || y >= (position.1 + region.h).saturating_sub(border_thickness)
+                {
+                    Some(border_style.color)
+                } else {
+                    self.style.fill_style
                 }
-            } else {
-                None
-            };
+            }
+            None => self.style.fill_style,
+        }
+    }
+}
 
-            if let Some(color) = rgba {
-                pixel.copy_from_slice(&color);
+impl<Message> Element<Message> for Quad {
+    fn draw(&self, frame: &mut Buffer, _tree: &StateNode, region: CalculatedLayout) {
+        let visible = region.intersection(&frame.clip());
+
+        // only visit the pixels that can actually be drawn
+        for y in visible.y..(visible.y + visible.h) {
+            for x in visible.x..(visible.x + visible.w) {
+                if let Some(color) = self.color_at(region, (x, y)) {
+                    frame.set_pixel(x as usize, y as usize, color);
+                }
             }
         }
     }
@@ -130,4 +126,8 @@ impl<Message> Element<Message> for Quad {
     ) -> Option<Message> {
         None
     }
+
+    fn hit_test(&self, _tree: &StateNode, region: CalculatedLayout, pos: (u32, u32)) -> bool {
+        region.contains(pos) && self.color_at(region, pos).is_some()
+    }
 }
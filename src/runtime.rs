@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use log::warn;
+
+use crate::{
+    buffer::Buffer,
+    layout::{CalculatedLayout, LayoutError},
+    modal::{Layers, ModalLayer},
+    palette::Color,
+    state_tree::StateNode,
+    tree::Node,
+    Input,
+};
+
+/// The part of an app that doesn't care where its input comes from or where its pixels go.
+///
+/// Both the windowed [`crate::run`] and [`crate::headless::Headless`] drive one of these.
+pub(crate) struct Runtime<'a, State, Message, Update, View, Timer> {
+    pub state: State,
+    update: Update,
+    view: View,
+    timer: Timer,
+    width: u32,
+    height: u32,
+    clear_color: Color,
+    root: Node<'a, Message, CalculatedLayout>,
+    state_root: StateNode,
+    /// Drawn over `root`, and takes all the input while it is open
+    modal: Option<ModalLayer<'a, Message>>,
+    /// Everything that didn't fit the last time the view was laid out
+    layout_errors: Vec<LayoutError>,
+}
+
+impl<'a, State, Message, Update, View, Screen, Timer>
+    Runtime<'a, State, Message, Update, View, Timer>
+where
+    Update: Fn(Message, &mut State),
+    View: Fn(&State) -> Screen,
+    Screen: Into<Layers<'a, Message>>,
+    Timer: Fn(Duration) -> Option<Message>,
+{
+    pub fn new(
+        initial_state: State,
+        update: Update,
+        view: View,
+        width: u32,
+        height: u32,
+        clear_color: Color,
+        timer: Timer,
+    ) -> Self {
+        let layers = view(&initial_state).into();
+        let mut new_root = Node::root_node(width as usize, height as usize);
+        new_root.push(layers.main);
+        let state_root = StateNode::new(&new_root);
+        let mut layout_errors = vec![];
+        let root = new_root.calculate_layout_reporting(&mut layout_errors);
+        let modal = layers
+            .modal
+            .map(|modal| ModalLayer::new(modal, width, height, None, &mut layout_errors));
+        warn_layout_errors(&layout_errors);
+
+        Self {
+            state: initial_state,
+            update,
+            view,
+            timer,
+            width,
+            height,
+            clear_color,
+            root,
+            state_root,
+            modal,
+            layout_errors,
+        }
+    }
+
+    /// Feeds one frame of input (and the time since the last one) to the app, updating the
+    /// state and rebuilding the view if any messages were produced.
+    ///
+    /// Returns whether the view was rebuilt.
+    pub fn handle_input(&mut self, input: &Input, elapsed: Duration) -> bool {
+        // an open modal blocks the main view from seeing any input
+        let view_message = match &mut self.modal {
+            Some(modal) => modal.get_message(input),
+            None => self.root.get_message(&mut self.state_root, input),
+        };
+        let mut messages: Vec<_> = [view_message, (self.timer)(elapsed)]
+            .into_iter()
+            // Filters None values
+            .flatten()
+            .collect();
+        // time passes underneath an open modal too
+        self.root.tick(&mut self.state_root, elapsed, &mut messages);
+        if let Some(modal) = &mut self.modal {
+            modal.tick(elapsed, &mut messages);
+        }
+
+        let update_needed = !messages.is_empty();
+
+        messages
+            .into_iter()
+            .for_each(|message| (self.update)(message, &mut self.state));
+
+        if update_needed {
+            let layers = (self.view)(&self.state).into();
+            let mut new_root = Node::root_node(self.width as usize, self.height as usize);
+            new_root.push(layers.main);
+            self.state_root.diff(&new_root);
+            self.layout_errors.clear();
+            self.root = new_root.calculate_layout_reporting(&mut self.layout_errors);
+            let previous = self.modal.take().map(|modal| modal.state);
+            self.modal = layers.modal.map(|modal| {
+                ModalLayer::new(
+                    modal,
+                    self.width,
+                    self.height,
+                    previous,
+                    &mut self.layout_errors,
+                )
+            });
+            warn_layout_errors(&self.layout_errors);
+        }
+
+        update_needed
+    }
+
+    /// Whether Escape means something to the app right now: a modal is open to dismiss, or an
+    /// element like a text input is focused.
+    pub fn takes_escape(&self) -> bool {
+        self.modal.is_some() || self.root.has_focus(&self.state_root)
+    }
+
+    /// Everything that didn't fit the last time the view was laid out. The view is still drawn,
+    /// falling back as described on [`LayoutError`].
+    pub fn layout_errors(&self) -> &[LayoutError] {
+        &self.layout_errors
+    }
+
+    /// Clears `frame` and draws the current view into it.
+    pub fn draw(&self, frame: &mut [u8]) {
+        let mut frame = Buffer::new(frame, self.width as usize, self.height as usize);
+        frame.clear(self.clear_color);
+        self.root
+            .draw_recursive(&mut frame, &self.state_root, (0, 0));
+        if let Some(modal) = &self.modal {
+            modal.draw(&mut frame);
+        }
+    }
+}
+
+fn warn_layout_errors(errors: &[LayoutError]) {
+    for error in errors {
+        warn!("layout: {error}");
+    }
+}
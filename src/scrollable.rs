@@ -0,0 +1,192 @@
+use crate::{
+    buffer::Buffer,
+    element::Element,
+    layout::{CalculatedLayout, Layout, Length},
+    palette::{Color, BLUE_DARK, BLUE_LIGHT},
+    state_tree::{self, StateNode},
+    tree::Node,
+    Input,
+};
+
+/// Width of the scrollbar, which sits in the node's right padding
+const SCROLLBAR_WIDTH: u32 = 3;
+const MIN_THUMB_HEIGHT: u32 = 4;
+
+/// A column whose children keep their natural height, however little room it has. Whatever
+/// doesn't fit can be scrolled into view with the mouse wheel or the scrollbar on the right.
+///
+/// Push children onto the node it turns into, like any other container. The node grows to fill
+/// its parent's height by default.
+pub struct Scrollable {
+    /// The area the children take up when not scrolled
+    content: CalculatedLayout,
+    track_color: Color,
+    thumb_color: Color,
+}
+
+pub struct State {
+    /// How far down the content is scrolled, in pixels
+    offset: u32,
+    /// Where on the thumb the mouse holds it, while the scrollbar is being dragged
+    grab: Option<u32>,
+}
+
+impl Default for Scrollable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scrollable {
+    pub fn new() -> Self {
+        Self {
+            content: CalculatedLayout::default(),
+            track_color: BLUE_LIGHT,
+            thumb_color: BLUE_DARK,
+        }
+    }
+
+    pub fn set_color(mut self, track: Color, thumb: Color) -> Self {
+        self.track_color = track;
+        self.thumb_color = thumb;
+        self
+    }
+
+    /// How far the content can be scrolled before its bottom reaches the bottom of `region`.
+    fn max_offset(&self, region: CalculatedLayout) -> u32 {
+        (self.content.y + self.content.h).saturating_sub(region.y + region.h)
+    }
+
+    fn track(&self, region: CalculatedLayout) -> CalculatedLayout {
+        CalculatedLayout {
+            x: (region.x + region.w).saturating_sub(SCROLLBAR_WIDTH),
+            w: SCROLLBAR_WIDTH.min(region.w),
+            ..region
+        }
+    }
+
+    /// The part of the scrollbar that can be dragged. `None` when everything fits.
+    fn thumb(&self, region: CalculatedLayout, offset: u32) -> Option<CalculatedLayout> {
+        let max_offset = self.max_offset(region);
+        if max_offset == 0 {
+            return None;
+        }
+        let track = self.track(region);
+        // the thumb is to the track what the viewport is to the content
+        let h = (region.h * region.h / (region.h + max_offset))
+            .max(MIN_THUMB_HEIGHT)
+            .min(region.h);
+        let y = region.y + offset.min(max_offset) * (region.h - h) / max_offset;
+        Some(CalculatedLayout { y, h, ..track })
+    }
+}
+
+impl<Message> Element<Message> for Scrollable {
+    fn draw(&self, frame: &mut Buffer, tree: &StateNode, region: CalculatedLayout) {
+        let state = tree.state.downcast_ref::<State>();
+        let Some(thumb) = self.thumb(region, state.offset) else {
+            return;
+        };
+
+        let track = self.track(region);
+        let visible = track.intersection(&frame.clip());
+        for y in visible.y..(visible.y + visible.h) {
+            for x in visible.x..(visible.x + visible.w) {
+                let color = if thumb.contains((x, y)) {
+                    self.thumb_color
+                } else {
+                    self.track_color
+                };
+                frame.set_pixel(x as usize, y as usize, color);
+            }
+        }
+    }
+
+    fn get_message(
+        &mut self,
+        tree: &mut StateNode,
+        input: &Input,
+        region: CalculatedLayout,
+    ) -> Option<Message> {
+        let state = tree.state.downcast_mut::<State>();
+        let max_offset = self.max_offset(region);
+
+        // the content might have shrunk since the last frame
+        state.offset = state.offset.min(max_offset);
+
+        let hovered = input.mouse_pos.is_some_and(|pos| region.contains(pos));
+        if hovered && input.wheel_delta.1 != 0 {
+            state.offset = (state.offset as i64 - input.wheel_delta.1 as i64)
+                .clamp(0, max_offset as i64) as u32;
+        }
+
+        match (
+            input.mouse_down,
+            input.mouse_pos,
+            self.thumb(region, state.offset),
+        ) {
+            (true, Some(mouse_pos), Some(thumb)) => {
+                if state.grab.is_none() && self.track(region).contains(mouse_pos) {
+                    // grabbing the track instead of the thumb jumps the thumb's middle there
+                    state.grab = Some(match thumb.contains(mouse_pos) {
+                        true => mouse_pos.1 - thumb.y,
+                        false => thumb.h / 2,
+                    });
+                }
+                if let Some(grab) = state.grab {
+                    let thumb_y = mouse_pos.1.saturating_sub(region.y + grab);
+                    let travel = region.h - thumb.h;
+                    state.offset = match travel {
+                        0 => 0,
+                        travel => (thumb_y * max_offset / travel).min(max_offset),
+                    };
+                }
+            }
+            (true, _, _) => {}
+            (false, _, _) => state.grab = None,
+        }
+
+        None
+    }
+
+    fn hit_test(&self, _tree: &StateNode, region: CalculatedLayout, pos: (u32, u32)) -> bool {
+        region.contains(pos)
+    }
+
+    fn children_positioned(&mut self, content: CalculatedLayout) {
+        self.content = content;
+    }
+
+    fn scroll_offset(&self, tree: &StateNode, region: CalculatedLayout) -> Option<(u32, u32)> {
+        let state = tree.state.downcast_ref::<State>();
+        Some((0, state.offset.min(self.max_offset(region))))
+    }
+
+    fn tag(&self) -> state_tree::Tag {
+        state_tree::Tag::of::<State>()
+    }
+
+    fn get_initial_state(&self) -> state_tree::State {
+        state_tree::State::new(State {
+            offset: 0,
+            grab: None,
+        })
+    }
+}
+
+impl<'a, Message> From<Scrollable> for Node<'a, Message, Layout> {
+    fn from(value: Scrollable) -> Self {
+        let node =
+            Node::new(value)
+                .column()
+                .height(Length::Grow)
+                .padding([0, SCROLLBAR_WIDTH, 0, 0]);
+        Node {
+            layout: Layout {
+                scroll: true,
+                ..node.layout
+            },
+            ..node
+        }
+    }
+}
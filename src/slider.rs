@@ -1,5 +1,5 @@
 use crate::{
-    constants::WIDTH,
+    buffer::Buffer,
     element::Element,
     layout::{CalculatedLayout, Layout},
     palette::{Color, BLUE_DARK, BLUE_LIGHT, MAIN_DARK, MAIN_LIGHT, RED_DARK, RED_LIGHT},
@@ -56,15 +56,12 @@ impl<Message> Slider<Message> {
 }
 
 impl<Message> Element<Message> for Slider<Message> {
-    fn draw(&self, frame: &mut [u8], region: CalculatedLayout) {
+    fn draw(&self, frame: &mut Buffer, _tree: &StateNode, region: CalculatedLayout) {
         let percent = (self.value - self.range.start) / (self.range.end - self.range.start);
 
-        let bar_y_start = region.y + region.h / 2 - 1;
+        let bar_y_start = (region.y + region.h / 2) as i32 - 1;
 
-        let frame_index = |i: i32, j: i32| {
-            ((((region.x as i32) + i) as u32 + (((bar_y_start as i32) + j) as u32) * WIDTH) * 4)
-                as usize
-        };
+        let frame_position = |i: i32, j: i32| (region.x as i32 + i, bar_y_start + j);
         for j in 0..2 {
             for i in 0..region.w {
                 let pixel = if (i as f32 / region.w as f32) < percent {
@@ -72,21 +69,21 @@ impl<Message> Element<Message> for Slider<Message> {
                 } else {
                     self.inactive_bar
                 };
-                let index = frame_index(i as i32, j as i32);
-                frame[index..(index + 4)].copy_from_slice(&pixel);
+                let (x, y) = frame_position(i as i32, j);
+                frame.set_pixel_signed(x, y, pixel);
             }
         }
 
         let handle_i = (percent * region.w as f32).round() as i32;
-        let handle_indexes = [
-            frame_index(handle_i + 1, -1),
-            frame_index(handle_i, 0),
-            frame_index(handle_i, 1),
-            frame_index(handle_i - 1, 2),
+        let handle_positions = [
+            frame_position(handle_i + 1, -1),
+            frame_position(handle_i, 0),
+            frame_position(handle_i, 1),
+            frame_position(handle_i - 1, 2),
         ];
-        handle_indexes
+        handle_positions
             .into_iter()
-            .for_each(|i| frame[i..(i + 4)].copy_from_slice(&self.handle));
+            .for_each(|(x, y)| frame.set_pixel_signed(x, y, self.handle));
     }
 
     fn get_message(
@@ -117,6 +114,14 @@ impl<Message> Element<Message> for Slider<Message> {
         return None;
     }
 
+    fn hit_test(&self, _tree: &StateNode, region: CalculatedLayout, pos: (u32, u32)) -> bool {
+        region.contains(pos)
+    }
+
+    fn tag(&self) -> state_tree::Tag {
+        state_tree::Tag::of::<State>()
+    }
+
     fn get_initial_state(&self) -> state_tree::State {
         state_tree::State::new(State { is_dragging: false })
     }
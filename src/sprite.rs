@@ -0,0 +1,252 @@
+use std::{rc::Rc, time::Duration};
+
+use crate::{
+    buffer::Buffer,
+    element::Element,
+    image::Image,
+    layout::{CalculatedLayout, Layout},
+    palette::Color,
+    state_tree::{self, StateNode},
+    tree::Node,
+    Input,
+};
+
+/// The frames of an animation, cut out of one atlas image.
+///
+/// Cheap to clone, so views can keep one in their state and hand out copies.
+#[derive(Clone)]
+pub struct SpriteSheet {
+    frames: Rc<[Image<Color>]>,
+}
+
+impl SpriteSheet {
+    /// Slices `atlas` into frames of `frame_width` by `frame_height`, going along each row before
+    /// moving down. Frames that would run past the edge of the atlas are left out. The frames
+    /// keep the atlas's scale factor.
+    pub fn new(atlas: Image<Color>, frame_width: usize, frame_height: usize) -> Self {
+        assert!(frame_width != 0 && frame_height != 0);
+        let columns = atlas.width / frame_width;
+        let rows = atlas.height / frame_height;
+        let frames = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let data = (0..frame_height)
+                    .flat_map(|j| {
+                        let start = (row * frame_height + j) * atlas.width + column * frame_width;
+                        atlas.data[start..(start + frame_width)].iter().copied()
+                    })
+                    .collect();
+                Image::new(data, frame_width, frame_height).scale_factor(atlas.scale_factor)
+            })
+            .collect();
+        Self { frames }
+    }
+
+    /// Uses already separate images as the frames.
+    pub fn from_frames(frames: Vec<Image<Color>>) -> Self {
+        Self {
+            frames: frames.into(),
+        }
+    }
+
+    pub fn frame(&self, index: usize) -> &Image<Color> {
+        &self.frames[index]
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// What an [`AnimatedSprite`] does after its last frame.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Playback {
+    /// Stops on the last frame
+    Once,
+    /// Starts over from the first frame
+    #[default]
+    Loop,
+    /// Plays backwards to the first frame, then forwards again
+    PingPong,
+}
+
+/// Plays the frames of a [`SpriteSheet`], moving on as time passes.
+///
+/// Where it is in the animation is kept in widget state, so rebuilding the view doesn't restart
+/// it. Give it a [key](Node::key) when it moves around among its siblings.
+pub struct AnimatedSprite<Message> {
+    sheet: SpriteSheet,
+    /// How long each frame is shown, repeating the last one for frames past the end
+    durations: Vec<Duration>,
+    playback: Playback,
+    on_finish: Option<Box<dyn Fn() -> Message>>,
+}
+
+struct State {
+    frame: usize,
+    /// How long the current frame has been shown
+    shown_for: Duration,
+    /// Whether a ping-pong animation is currently playing forwards
+    forwards: bool,
+    /// Set once a [`Playback::Once`] animation reaches its last frame
+    finished: bool,
+}
+
+impl<Message> AnimatedSprite<Message> {
+    /// Shows each frame of `sheet` for 100ms, looping.
+    pub fn new(sheet: SpriteSheet) -> Self {
+        Self {
+            sheet,
+            durations: vec![Duration::from_millis(100)],
+            playback: Playback::default(),
+            on_finish: None,
+        }
+    }
+
+    /// Shows every frame for `duration`.
+    pub fn frame_duration(mut self, duration: Duration) -> Self {
+        assert!(!duration.is_zero());
+        self.durations = vec![duration];
+        self
+    }
+
+    /// Shows each frame for its own duration. Frames past the end of `durations` use the last
+    /// one.
+    pub fn frame_durations(mut self, durations: Vec<Duration>) -> Self {
+        assert!(!durations.is_empty() && durations.iter().all(|d| !d.is_zero()));
+        self.durations = durations;
+        self
+    }
+
+    pub fn playback(mut self, playback: Playback) -> Self {
+        self.playback = playback;
+        self
+    }
+
+    /// Sent when a [`Playback::Once`] animation stops, and every time a looping or ping-pong
+    /// animation is back at its first frame.
+    pub fn on_finish<F>(mut self, m: F) -> Self
+    where
+        F: Fn() -> Message + 'static,
+    {
+        self.on_finish = Some(Box::new(m));
+        self
+    }
+
+    fn duration(&self, frame: usize) -> Duration {
+        self.durations[frame.min(self.durations.len() - 1)]
+    }
+
+    /// Moves `state` on by one frame, returning whether that finished the animation.
+    fn advance(&self, state: &mut State) -> bool {
+        let last = self.sheet.len().saturating_sub(1);
+        match self.playback {
+            Playback::Once if state.frame >= last => {
+                state.finished = true;
+                true
+            }
+            Playback::Once => {
+                state.frame += 1;
+                false
+            }
+            Playback::Loop if state.frame >= last => {
+                state.frame = 0;
+                true
+            }
+            Playback::Loop => {
+                state.frame += 1;
+                false
+            }
+            Playback::PingPong => {
+                if state.forwards && state.frame >= last {
+                    state.forwards = false;
+                } else if !state.forwards && state.frame == 0 {
+                    state.forwards = true;
+                }
+                match state.forwards {
+                    true => state.frame = (state.frame + 1).min(last),
+                    false => state.frame = state.frame.saturating_sub(1),
+                }
+                !state.forwards && state.frame == 0
+            }
+        }
+    }
+}
+
+impl<Message> Element<Message> for AnimatedSprite<Message> {
+    fn draw(&self, frame: &mut Buffer, tree: &StateNode, region: CalculatedLayout) {
+        let state = tree.state.downcast_ref::<State>();
+        if let Some(image) = self.sheet.frames.get(state.frame) {
+            Element::<Message>::draw(image, frame, tree, region);
+        }
+    }
+
+    fn get_message(
+        &mut self,
+        _tree: &mut StateNode,
+        _input: &Input,
+        _region: CalculatedLayout,
+    ) -> Option<Message> {
+        None
+    }
+
+    fn tick(&mut self, tree: &mut StateNode, elapsed: Duration) -> Option<Message> {
+        let state = tree.state.downcast_mut::<State>();
+        // the sheet might have fewer frames than the one it replaced
+        state.frame = state.frame.min(self.sheet.len().saturating_sub(1));
+        if state.finished || self.sheet.is_empty() {
+            return None;
+        }
+
+        state.shown_for += elapsed;
+        let mut finished = false;
+        // a long frame can skip several
+        while !state.finished && state.shown_for >= self.duration(state.frame) {
+            state.shown_for -= self.duration(state.frame);
+            finished |= self.advance(state);
+        }
+        match finished {
+            true => self.on_finish.as_ref().map(|m| m()),
+            false => None,
+        }
+    }
+
+    fn min_width(&self) -> u32 {
+        self.sheet
+            .frames
+            .first()
+            .map(|f| Element::<Message>::min_width(f))
+            .unwrap_or_default()
+    }
+
+    fn min_height(&self, width: u32) -> u32 {
+        self.sheet
+            .frames
+            .first()
+            .map(|f| Element::<Message>::min_height(f, width))
+            .unwrap_or_default()
+    }
+
+    fn tag(&self) -> state_tree::Tag {
+        state_tree::Tag::of::<State>()
+    }
+
+    fn get_initial_state(&self) -> state_tree::State {
+        state_tree::State::new(State {
+            frame: 0,
+            shown_for: Duration::ZERO,
+            forwards: true,
+            finished: false,
+        })
+    }
+}
+
+impl<'a, Message: 'static> From<AnimatedSprite<Message>> for Node<'a, Message, Layout> {
+    fn from(value: AnimatedSprite<Message>) -> Self {
+        Node::new(value)
+    }
+}
@@ -1,20 +1,26 @@
 // See Iced, which we took most of this from at least to start
 
-use std::any::Any;
+use std::any::{self, Any};
 
 use crate::tree::Node;
 
-// #[derive(Debug)]
-// pub struct Tag(any::TypeId);
+/// Identifies the kind of state a widget keeps, so state is never handed to a widget that
+/// expects a different type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tag(any::TypeId);
 
-// impl Tag {
-//     pub fn of<T>() -> Self
-//     where
-//         T: 'static,
-//     {
-//         Self(any::TypeId::of::<T>())
-//     }
-// }
+impl Tag {
+    pub fn of<T>() -> Self
+    where
+        T: 'static,
+    {
+        Self(any::TypeId::of::<T>())
+    }
+
+    pub fn stateless() -> Self {
+        Self::of::<()>()
+    }
+}
 
 pub enum State {
     None,
@@ -29,6 +35,16 @@ impl State {
         State::Some(Box::new(state))
     }
 
+    pub fn downcast_ref<T>(&self) -> &T
+    where
+        T: 'static,
+    {
+        match self {
+            State::None => panic!("Downcast on stateless state"),
+            State::Some(state) => state.downcast_ref().expect("Downcast widget state"),
+        }
+    }
+
     pub fn downcast_mut<T>(&mut self) -> &mut T
     where
         T: 'static,
@@ -41,7 +57,8 @@ impl State {
 }
 
 pub struct StateNode {
-    // pub tag: Tag,
+    pub tag: Tag,
+    pub key: Option<u64>,
     pub state: State,
     pub children: Vec<StateNode>,
 }
@@ -49,7 +66,8 @@ pub struct StateNode {
 impl StateNode {
     pub fn new<Message, Layout>(node: &Node<Message, Layout>) -> Self {
         Self {
-            // tag: , // TODO when changing layout
+            tag: node.element.tag(),
+            key: node.key,
             state: node.element.get_initial_state(),
             children: node
                 .children
@@ -58,4 +76,50 @@ impl StateNode {
                 .collect(),
         }
     }
+
+    /// Brings this state tree in line with a freshly built view, keeping the state of every
+    /// widget that is still there.
+    ///
+    /// Keyed children are matched by key, the rest by their order among the unkeyed children.
+    /// State is only reused when the widget's [`Tag`] matches; anything new starts from its
+    /// initial state and anything missing is dropped.
+    pub fn diff<Message, Layout>(&mut self, node: &Node<Message, Layout>) {
+        if self.tag != node.element.tag() {
+            *self = Self::new(node);
+            return;
+        }
+        self.key = node.key;
+
+        let mut old_children: Vec<_> = std::mem::take(&mut self.children)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut unkeyed_old = old_children
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| child.as_ref().is_some_and(|c| c.key.is_none()))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        self.children = node
+            .children
+            .iter()
+            .map(|child| {
+                let old_index = match child.key {
+                    Some(key) => old_children
+                        .iter()
+                        .position(|old| old.as_ref().is_some_and(|old| old.key == Some(key))),
+                    None => unkeyed_old.next(),
+                };
+                match old_index.and_then(|i| old_children[i].take()) {
+                    Some(mut old) => {
+                        old.diff(child);
+                        old
+                    }
+                    None => Self::new(child),
+                }
+            })
+            .collect();
+    }
 }
@@ -1,22 +1,21 @@
-use std::sync::LazyLock;
+use std::ops::Range;
 
 use crate::buffer::Buffer;
 use crate::element::Element;
-use crate::font::{self, Font, FontType};
+use crate::font::{self, Font, FontStack, FontType};
 use crate::layout::CalculatedLayout;
 use crate::palette::{Color, BLUE_LIGHT};
 use crate::state_tree::StateNode;
 
-const WIDTH: u32 = 320; // TODO make this metadata for the frame buffer
-                        //
 #[derive(Clone)]
 pub struct Text {
     pub content: String,
-    pub font: &'static LazyLock<FontType>,
+    pub font: &'static FontType,
+    /// Tried in order for characters `font` doesn't have
+    pub fallbacks: Vec<&'static FontType>,
     x_spacing: u32,
     y_spacing: u32,
     color: Color,
-    //pub font: &'static FontType,
 }
 
 impl Text {
@@ -24,6 +23,7 @@ impl Text {
         Self {
             content,
             font: &font::OLDSCHOOL,
+            fallbacks: vec![],
             x_spacing: 1,
             y_spacing: 1,
             color: BLUE_LIGHT,
@@ -34,73 +34,68 @@ impl Text {
         width.next_multiple_of(6) - 6
     }
 
-    pub fn with_font(self, font: &'static LazyLock<FontType>) -> Self {
+    /// Fonts loaded at runtime can be kept for the rest of the program with `Box::leak`.
+    pub fn with_font(self, font: &'static FontType) -> Self {
         Self { font, ..self }
     }
+    /// Adds a font to draw the characters that the font and earlier fallbacks don't have.
+    pub fn with_fallback(mut self, font: &'static FontType) -> Self {
+        self.fallbacks.push(font);
+        self
+    }
     pub fn with_color(self, color: Color) -> Self {
         Self { color, ..self }
     }
+    /// Where each line ends up when the text is laid out `width` wide, relative to its top left
+    /// corner, for finding what's under a point.
+    pub fn line_boxes(&self, width: u32) -> Vec<LineBox> {
+        break_lines(
+            &self.fonts(),
+            &self.content,
+            self.x_spacing as usize,
+            self.y_spacing as usize,
+            width as usize,
+        )
+    }
+
+    fn fonts(&self) -> FontStack<'_> {
+        FontStack::new(self.font, &self.fallbacks)
+    }
     fn hard_wrap(&self, width: u32) -> Option<u32> {
-        let length = self.content.len() as u32 * (self.font.width() as u32 + self.x_spacing);
+        let font = self.fonts();
+        let length = self.content.len() as u32 * (font.width() as u32 + self.x_spacing);
         let lines = length / Self::usable_width(width) + 1; // TODO + 1 is a hack
 
-        Some(lines * (self.font.height() as u32 + self.y_spacing) - self.y_spacing)
+        Some(lines * (font.height() as u32 + self.y_spacing) - self.y_spacing)
     }
 
     fn get_max_word_px(&self) -> u32 {
         self.content
-            .split(' ')
-            .map(|word| word_length(self.font, word, self.x_spacing as usize))
+            .split(BREAKING_WHITESPACE)
+            .map(|word| word_length(&self.fonts(), word, self.x_spacing as usize))
             .max()
             .unwrap_or_default() as u32
     }
 }
 
 impl<Message> Element<Message> for Text {
-    fn draw(&self, frame: &mut [u8], region: CalculatedLayout) {
-        let font = &self.font;
-        let y_per_char = self.font.height() as u32 + self.y_spacing;
-        let wrap_points = wrap_variable_width(
-            font,
-            &self.content,
-            self.x_spacing as usize,
-            region.w as usize,
-        );
-
-        // wrap_points and self.content.split(" ") should always be
-        // the same length. are we correct on that assumption?
-        assert_eq!(wrap_points.len(), self.content.split(' ').count());
-
-        self.content
-            .split(' ')
-            .zip(wrap_points)
-            .for_each(|(word, (start_x_pos, current_row))| {
-                word.chars()
-                    .enumerate()
-                    .scan(start_x_pos, |x_pos, (_, character)| {
-                        let (frame_x, frame_y) = (region.x, region.y);
-
-                        let line_number = current_row as u32;
-
-                        let char_x = frame_x + *x_pos as u32;
-                        let char_y = frame_y + line_number * y_per_char;
-
-                        let char_width = font.draw_character(
-                            &mut Buffer {
-                                data: frame,
-                                width: WIDTH as usize,
-                            },
-                            char_x as usize,
-                            char_y as usize,
-                            character,
-                            self.color,
-                        );
-
-                        *x_pos += char_width + self.x_spacing as usize;
-                        Some(*x_pos)
-                    })
-                    .last();
-            });
+    fn draw(&self, frame: &mut Buffer, _tree: &StateNode, region: CalculatedLayout) {
+        let font = self.fonts();
+        for line in self.line_boxes(region.w) {
+            for (word, word_x) in line.words {
+                for (x_pos, character, _) in
+                    character_positions(&font, &self.content[word], self.x_spacing as usize)
+                {
+                    font.draw_character(
+                        frame,
+                        (region.x + line.bounds.x) as usize + word_x + x_pos,
+                        (region.y + line.bounds.y) as usize,
+                        character,
+                        self.color,
+                    );
+                }
+            }
+        }
     }
 
     //TODO: Dead code
@@ -109,16 +104,10 @@ impl<Message> Element<Message> for Text {
         if hard_wrap {
             self.hard_wrap(width)
         } else {
-            let positions = wrap_variable_width(
-                self.font,
-                &self.content,
-                self.x_spacing as usize,
-                width as usize,
-            );
-            //TODO: don't add final character spacing
-            let (_, num_rows) = *positions.last().unwrap_or(&(0, 0));
-
-            Some((num_rows as u32 + 1) * (self.font.height() as u32 + self.y_spacing))
+            let font = self.fonts();
+            let lines = self.line_boxes(width).len() as u32;
+            //TODO: don't add final line spacing
+            Some(lines * (font.height() as u32 + self.y_spacing))
         }
     }
 
@@ -136,43 +125,122 @@ impl<Message> Element<Message> for Text {
     }
 }
 
-pub fn wrap_variable_width(
-    font: &FontType,
+/// Characters text can be broken at. Anything else, like a no-break space, belongs to the word
+/// around it.
+const BREAKING_WHITESPACE: [char; 4] = [' ', '\t', '\n', '\r'];
+
+/// Tab stops are this many spaces apart
+const TAB_SPACES: usize = 4;
+
+/// One line of text laid out by [`break_lines`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LineBox {
+    /// Byte range of the content on this line, including the whitespace that ends it
+    pub range: Range<usize>,
+    /// Byte range of each word on the line, and how far from the start of the line it's drawn
+    pub words: Vec<(Range<usize>, usize)>,
+    /// Relative to the top left corner of the text, as wide as the line's words reach and as
+    /// tall as the font
+    pub bounds: CalculatedLayout,
+}
+
+/// Splits `content` into lines no wider than `width_px` where it can, breaking between words.
+///
+/// `\n` always starts a new line. Spaces each take up the width of a space, so runs of them are
+/// kept, and tabs move on to the next tab stop. Whitespace where a line wraps stays at the end of
+/// the line it follows. A word too long for the width gets a line of its own and runs past the
+/// end of it.
+pub fn break_lines(
+    font: &impl Font,
     content: &str,
     character_padding: usize,
+    line_padding: usize,
     width_px: usize,
-) -> Vec<(usize, usize)> {
+) -> Vec<LineBox> {
     let space_width = font.glyph_width(' ');
-    content
-        .split(' ')
-        .scan((0, 0), |(current_column, current_row), word| {
-            let word_length = word_length(font, word, character_padding);
-            // We don't handle this yet
-            assert!(word_length <= width_px);
+    let tab_width = (space_width * TAB_SPACES).max(1);
+    let line_height = font.height() as u32;
+    let new_line = |lines: &Vec<LineBox>, start: usize| LineBox {
+        range: start..start,
+        words: vec![],
+        bounds: CalculatedLayout {
+            x: 0,
+            y: lines.len() as u32 * (line_height + line_padding as u32),
+            w: 0,
+            h: line_height,
+        },
+    };
 
-            // 3 cases:
-            let (next_row, start_column) = if *current_column == 0 {
-                // Very first word
-                (0, 0)
-            } else if (*current_column + word_length + space_width) >= width_px {
-                // We need to wrap
-                (*current_row + 1, 0)
-            } else {
-                // Move along the row
-                (*current_row, *current_column + space_width)
-            };
+    let mut lines = vec![];
+    lines.push(new_line(&lines, 0));
+    let mut x = 0;
+    for (start, token) in tokens(content) {
+        let end = start + token.len();
+        match token {
+            "\n" => {
+                lines.last_mut().unwrap().range.end = end;
+                lines.push(new_line(&lines, end));
+                x = 0;
+                continue;
+            }
+            " " => x += space_width,
+            "\t" => x = (x / tab_width + 1) * tab_width,
+            "\r" => {}
+            word => {
+                let length = word_length(font, word, character_padding);
+                let line = lines.last_mut().unwrap();
+                if !line.words.is_empty() && x + length >= width_px {
+                    line.range.end = start;
+                    lines.push(new_line(&lines, start));
+                    x = 0;
+                }
+                let line = lines.last_mut().unwrap();
+                line.words.push((start..end, x));
+                x += length;
+                line.bounds.w = x as u32;
+            }
+        }
+        lines.last_mut().unwrap().range.end = end;
+    }
+    lines
+}
 
-            *current_row = next_row;
-            *current_column = start_column + word_length;
-            Some((start_column, *current_row))
+/// Splits `content` into words and single whitespace characters, with where each starts
+fn tokens(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let rest = &content[offset..];
+        let first = rest.chars().next()?;
+        let length = match BREAKING_WHITESPACE.contains(&first) {
+            true => 1,
+            false => rest.find(BREAKING_WHITESPACE).unwrap_or(rest.len()),
+        };
+        offset += length;
+        Some((offset - length, &rest[..length]))
+    })
+}
+
+/// Each character of `text` with where it starts and where the next one would start, measured
+/// from the start of the text, with kerning applied between neighbours
+pub(crate) fn character_positions<'a>(
+    font: &'a impl Font,
+    text: &'a str,
+    character_padding: usize,
+) -> impl Iterator<Item = (usize, char, usize)> + 'a {
+    text.chars()
+        .scan((0usize, None), move |(x, previous), character| {
+            let kerning = previous.map_or(0, |previous| font.kerning(previous, character));
+            let start = x.saturating_add_signed(kerning as isize);
+            *x = start + font.glyph_width(character) + character_padding;
+            *previous = Some(character);
+            Some((start, character, *x))
         })
-        .collect()
 }
 
-fn word_length(font: &FontType, word: &str, character_padding: usize) -> usize {
-    word.chars()
-        .map(|c| font.glyph_width(c) + character_padding)
-        .sum()
+pub(crate) fn word_length(font: &impl Font, word: &str, character_padding: usize) -> usize {
+    character_positions(font, word, character_padding)
+        .last()
+        .map_or(0, |(_, _, end)| end)
 }
 
 #[cfg(test)]
@@ -0,0 +1,323 @@
+use crate::{
+    buffer::Buffer,
+    element::Element,
+    font::{self, Font, FontStack, FontType},
+    layout::{CalculatedLayout, Layout},
+    palette::{Color, BLUE_DARK, BLUE_LIGHT, MAIN_DARK},
+    state_tree::{self, StateNode},
+    text::{character_positions, word_length},
+    tree::Node,
+    Input, KeyCode,
+};
+
+/// A single line of editable text.
+///
+/// Like the rest of icecube, the value belongs to the app: edits are reported through
+/// `on_change` and only show up once `view` passes the new value back in.
+pub struct TextInput<Message> {
+    value: String,
+    font: &'static FontType,
+    fallbacks: Vec<&'static FontType>,
+    x_spacing: u32,
+    color: Color,
+    caret_color: Color,
+    selection_color: Color,
+    on_change: Option<Box<dyn Fn(String) -> Message>>,
+    on_submit: Option<Message>,
+}
+
+/// Caret and selection, as character (not byte) offsets into the value.
+pub struct State {
+    is_focused: bool,
+    is_dragging: bool,
+    cursor: usize,
+    /// The other end of the selection. Equal to `cursor` when nothing is selected.
+    anchor: usize,
+}
+
+impl State {
+    fn selection(&self) -> Option<std::ops::Range<usize>> {
+        if self.cursor == self.anchor {
+            None
+        } else {
+            Some(self.cursor.min(self.anchor)..self.cursor.max(self.anchor))
+        }
+    }
+
+    fn move_to(&mut self, position: usize, extend_selection: bool) {
+        self.cursor = position;
+        if !extend_selection {
+            self.anchor = position;
+        }
+    }
+}
+
+impl<Message> TextInput<Message> {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            font: &font::OLDSCHOOL,
+            fallbacks: vec![],
+            x_spacing: 1,
+            color: BLUE_LIGHT,
+            caret_color: MAIN_DARK,
+            selection_color: BLUE_DARK,
+            on_change: None,
+            on_submit: None,
+        }
+    }
+
+    pub fn with_font(self, font: &'static FontType) -> Self {
+        Self { font, ..self }
+    }
+
+    /// Adds a font to draw the characters that the font and earlier fallbacks don't have.
+    pub fn with_fallback(mut self, font: &'static FontType) -> Self {
+        self.fallbacks.push(font);
+        self
+    }
+
+    pub fn with_color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn set_color(mut self, text: Color, caret: Color, selection: Color) -> Self {
+        self.color = text;
+        self.caret_color = caret;
+        self.selection_color = selection;
+        self
+    }
+
+    // TODO allow unsetting a message?
+    pub fn on_change<F>(mut self, m: F) -> Self
+    where
+        F: Fn(String) -> Message + 'static,
+    {
+        self.on_change = Some(Box::new(m));
+        self
+    }
+
+    pub fn on_submit(mut self, m: Message) -> Self {
+        self.on_submit = Some(m);
+        self
+    }
+
+    fn fonts(&self) -> FontStack<'_> {
+        FontStack::new(self.font, &self.fallbacks)
+    }
+
+    fn char_count(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    fn byte_offset(&self, char_offset: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_offset)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+
+    /// Horizontal pixel offset of the caret when it is before the character at `char_offset`.
+    fn x_of(&self, char_offset: usize) -> u32 {
+        let end = self.byte_offset(char_offset);
+        word_length(&self.fonts(), &self.value[..end], self.x_spacing as usize) as u32
+    }
+
+    /// The caret position closest to a pixel offset from the left edge of the text.
+    fn char_at(&self, x: u32) -> usize {
+        let font = self.fonts();
+        let position = character_positions(&font, &self.value, self.x_spacing as usize)
+            .position(|(start, _, end)| (x as usize) < start + (end - start) / 2);
+        position.unwrap_or(self.char_count())
+    }
+
+    /// How far the text is shifted left so that the caret stays inside `region`.
+    fn scroll(&self, state_cursor: usize, region: CalculatedLayout) -> u32 {
+        (self.x_of(state_cursor) + 1).saturating_sub(region.w)
+    }
+
+    fn replace(&mut self, range: std::ops::Range<usize>, with: &str) {
+        let (start, end) = (self.byte_offset(range.start), self.byte_offset(range.end));
+        self.value.replace_range(start..end, with);
+    }
+
+    /// Applies this frame's keyboard input, returning whether the value changed.
+    fn edit(&mut self, state: &mut State, input: &Input) -> bool {
+        let mut changed = false;
+        let extend = input.modifiers.shift;
+        let pressed = |key| input.key_pressed_or_repeated(key);
+
+        if input.modifiers.control && input.key_pressed(KeyCode::KeyA) {
+            state.anchor = 0;
+            state.cursor = self.char_count();
+        }
+
+        if pressed(KeyCode::ArrowLeft) {
+            match state.selection() {
+                Some(selection) if !extend => state.move_to(selection.start, false),
+                _ => state.move_to(state.cursor.saturating_sub(1), extend),
+            }
+        }
+        if pressed(KeyCode::ArrowRight) {
+            match state.selection() {
+                Some(selection) if !extend => state.move_to(selection.end, false),
+                _ => state.move_to((state.cursor + 1).min(self.char_count()), extend),
+            }
+        }
+        if pressed(KeyCode::Home) {
+            state.move_to(0, extend);
+        }
+        if pressed(KeyCode::End) {
+            state.move_to(self.char_count(), extend);
+        }
+
+        if pressed(KeyCode::Backspace) {
+            let range = match state.selection() {
+                Some(selection) => Some(selection),
+                None if state.cursor > 0 => Some((state.cursor - 1)..state.cursor),
+                None => None,
+            };
+            if let Some(range) = range {
+                state.move_to(range.start, false);
+                self.replace(range, "");
+                changed = true;
+            }
+        }
+        if pressed(KeyCode::Delete) {
+            let range = match state.selection() {
+                Some(selection) => Some(selection),
+                None if state.cursor < self.char_count() => Some(state.cursor..(state.cursor + 1)),
+                None => None,
+            };
+            if let Some(range) = range {
+                state.move_to(range.start, false);
+                self.replace(range, "");
+                changed = true;
+            }
+        }
+
+        if !input.text.is_empty() && !input.modifiers.control {
+            let range = state.selection().unwrap_or(state.cursor..state.cursor);
+            self.replace(range.clone(), &input.text);
+            state.move_to(range.start + input.text.chars().count(), false);
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+impl<Message: Clone> Element<Message> for TextInput<Message> {
+    fn draw(&self, frame: &mut Buffer, tree: &StateNode, region: CalculatedLayout) {
+        let state = tree.state.downcast_ref::<State>();
+
+        frame.push_clip(region);
+
+        let scroll = self.scroll(state.cursor, region) as i32;
+        let text_x = region.x as i32 - scroll;
+        let font = self.fonts();
+        let height = font.height() as u32;
+
+        if state.is_focused {
+            if let Some(selection) = state.selection() {
+                let (start, end) = (self.x_of(selection.start), self.x_of(selection.end));
+                for y in region.y..(region.y + height) {
+                    for x in start..end {
+                        frame.set_pixel_signed(text_x + x as i32, y as i32, self.selection_color);
+                    }
+                }
+            }
+        }
+
+        for (x, character, _) in character_positions(&font, &self.value, self.x_spacing as usize) {
+            // Glyphs that start left of the frame can't be drawn by the fonts, so skip them
+            if let Ok(char_x) = usize::try_from(text_x + x as i32) {
+                font.draw_character(frame, char_x, region.y as usize, character, self.color);
+            }
+        }
+
+        if state.is_focused {
+            let caret_x = text_x + self.x_of(state.cursor) as i32;
+            for y in region.y..(region.y + height) {
+                frame.set_pixel_signed(caret_x, y as i32, self.caret_color);
+            }
+        }
+
+        frame.pop_clip();
+    }
+
+    fn get_message(
+        &mut self,
+        tree: &mut StateNode,
+        input: &Input,
+        region: CalculatedLayout,
+    ) -> Option<Message> {
+        let state = tree.state.downcast_mut::<State>();
+
+        // the value might have changed since the last frame
+        let length = self.char_count();
+        state.cursor = state.cursor.min(length);
+        state.anchor = state.anchor.min(length);
+
+        if input.mouse_down {
+            let inside = input.mouse_pos.is_some_and(|pos| region.contains(pos));
+            if !state.is_dragging {
+                state.is_focused = inside;
+            }
+            if let Some(mouse_pos) = input.mouse_pos {
+                if state.is_focused {
+                    let scroll = self.scroll(state.cursor, region);
+                    let position = self.char_at((mouse_pos.0 + scroll).saturating_sub(region.x));
+                    state.move_to(position, state.is_dragging);
+                    state.is_dragging = true;
+                }
+            }
+        } else {
+            state.is_dragging = false;
+        }
+
+        let mut message = None;
+        if state.is_focused {
+            let old_value = self.value.clone();
+            if self.edit(state, input) && self.value != old_value {
+                message = self.on_change.as_ref().map(|f| f(self.value.clone()));
+            } else if input.key_pressed(KeyCode::Enter) || input.key_pressed(KeyCode::NumpadEnter) {
+                message = self.on_submit.clone();
+            }
+        }
+
+        message
+    }
+
+    fn hit_test(&self, _tree: &StateNode, region: CalculatedLayout, pos: (u32, u32)) -> bool {
+        region.contains(pos)
+    }
+
+    fn min_height(&self, _width: u32) -> u32 {
+        self.fonts().height() as u32
+    }
+
+    fn is_focused(&self, tree: &StateNode) -> bool {
+        tree.state.downcast_ref::<State>().is_focused
+    }
+
+    fn tag(&self) -> state_tree::Tag {
+        state_tree::Tag::of::<State>()
+    }
+
+    fn get_initial_state(&self) -> state_tree::State {
+        state_tree::State::new(State {
+            is_focused: false,
+            is_dragging: false,
+            cursor: self.char_count(),
+            anchor: self.char_count(),
+        })
+    }
+}
+
+impl<'a, Message: Clone + 'static> From<TextInput<Message>> for Node<'a, Message, Layout> {
+    fn from(value: TextInput<Message>) -> Self {
+        Node::new(value)
+    }
+}
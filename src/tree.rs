@@ -1,8 +1,16 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    hash::{DefaultHasher, Hash, Hasher},
+    time::Duration,
+};
 
 use crate::{
+    buffer::Buffer,
     element::Element,
-    layout::{CalculatedLayout, Layout, LayoutDirection, Length, Padding},
+    layout::{
+        Align, Anchor, CalculatedLayout, Grid, GridCell, Justify, Layout, LayoutDirection, Length,
+        Padding,
+    },
     quad::{Quad, QuadStyle},
     state_tree::StateNode,
 };
@@ -12,12 +20,20 @@ pub struct Node<'a, Message, LayoutStage> {
     pub element: Box<dyn Element<Message> + 'a>,
     pub layout: LayoutStage, //Option<CalculatedLayout>,
     pub name: Option<String>,
+    /// Identifies this node among its siblings, so its widget state can follow it when the
+    /// children are reordered. See [`Node::key`].
+    pub key: Option<u64>,
+    /// Siblings with a higher z-index are drawn over this node and get input before it. Between
+    /// equal ones, later siblings are in front. See [`Node::z_index`].
+    pub z_index: i32,
 }
 
 impl<'a, Message, LayoutStage: Debug> Debug for Node<'a, Message, LayoutStage> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("")
             .field("name", &self.name)
+            .field("key", &self.key)
+            .field("z_index", &self.z_index)
             .field("layout", &self.layout)
             .field("children", &self.children)
             .finish()
@@ -53,6 +69,8 @@ impl<'a, Message> Node<'a, Message, Layout> {
                 ..Layout::default()
             },
             name: None,
+            key: None,
+            z_index: 0,
         }
     }
 
@@ -66,6 +84,16 @@ impl<'a, Message> Node<'a, Message, Layout> {
                 ..Layout::default()
             },
             name: None,
+            key: None,
+            z_index: 0,
+        }
+    }
+
+    /// Names this node, for debugging and for [`LayoutError`](crate::layout::LayoutError)s.
+    pub fn name(self, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
         }
     }
 
@@ -90,6 +118,54 @@ impl<'a, Message> Node<'a, Message, Layout> {
         }
     }
 
+    /// Keeps the width at or above `min_width`, whatever its [`Length`] would give it. A growing
+    /// node gets its share of the leftover space on top of this.
+    pub fn min_width(self, min_width: u32) -> Self {
+        Self {
+            layout: Layout {
+                min_width: Some(min_width),
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    /// Keeps the width at or below `max_width`. Space a growing node can't take goes to its
+    /// siblings.
+    pub fn max_width(self, max_width: u32) -> Self {
+        Self {
+            layout: Layout {
+                max_width: Some(max_width),
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    /// Keeps the height at or above `min_height`, whatever its [`Length`] would give it. A growing
+    /// node gets its share of the leftover space on top of this.
+    pub fn min_height(self, min_height: u32) -> Self {
+        Self {
+            layout: Layout {
+                min_height: Some(min_height),
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    /// Keeps the height at or below `max_height`. Space a growing node can't take goes to its
+    /// siblings.
+    pub fn max_height(self, max_height: u32) -> Self {
+        Self {
+            layout: Layout {
+                max_height: Some(max_height),
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
     pub fn padding(self, padding: impl Into<Padding>) -> Self {
         Self {
             layout: Layout {
@@ -110,6 +186,26 @@ impl<'a, Message> Node<'a, Message, Layout> {
         }
     }
 
+    pub fn justify(self, justify: Justify) -> Self {
+        Self {
+            layout: Layout {
+                justify,
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    pub fn align(self, align: Align) -> Self {
+        Self {
+            layout: Layout {
+                align,
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
     pub fn stack(self) -> Self {
         Self {
             layout: Layout {
@@ -140,6 +236,110 @@ impl<'a, Message> Node<'a, Message, Layout> {
         }
     }
 
+    /// Lays children out in a row that carries on below when it runs out of width. See
+    /// [`LayoutDirection::Wrap`].
+    pub fn wrap(self) -> Self {
+        Self {
+            layout: Layout {
+                direction: LayoutDirection::Wrap,
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    /// Lays children out in a grid with these column and row tracks. See [`Grid`].
+    pub fn grid(
+        self,
+        columns: impl IntoIterator<Item = impl Into<Length>>,
+        rows: impl IntoIterator<Item = impl Into<Length>>,
+    ) -> Self {
+        let columns = columns.into_iter().map(Into::into).collect();
+        let rows = rows.into_iter().map(Into::into).collect();
+        Self {
+            layout: Layout {
+                direction: LayoutDirection::Grid,
+                grid: Grid::new(columns, rows),
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    /// Puts this node in a particular cell of its parent's grid, counting from 0.
+    pub fn cell(self, column: u32, row: u32) -> Self {
+        Self {
+            layout: Layout {
+                cell: GridCell {
+                    column: Some(column),
+                    row: Some(row),
+                    ..self.layout.cell
+                },
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    /// Makes this node cover several columns and rows of its parent's grid.
+    pub fn span(self, columns: u32, rows: u32) -> Self {
+        Self {
+            layout: Layout {
+                cell: GridCell {
+                    column_span: columns,
+                    row_span: rows,
+                    ..self.layout.cell
+                },
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    /// Sets a key that is unique among this node's siblings. Widget state is matched to keyed
+    /// nodes by key instead of by position when the view is rebuilt, so it survives the list
+    /// being reordered or having items inserted.
+    pub fn key(self, key: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        Self {
+            key: Some(hasher.finish()),
+            ..self
+        }
+    }
+
+    /// Pins this node to `anchor` in its parent stack, moved `offset` pixels right and down from
+    /// there, instead of where the stack's justify and align would put it. Anchored nodes float
+    /// over their siblings: they don't count towards the size of a shrinking stack.
+    ///
+    /// Like any child, an anchored node is clipped to its parent stack, so it can't float out
+    /// past the stack's edges. Something like a dropdown should be anchored in a stack big
+    /// enough to hold it, such as one around the whole view, or shown in a modal.
+    ///
+    /// Has no effect unless the parent is a [`LayoutDirection::Stack`].
+    pub fn anchor(self, anchor: Anchor, offset: (i32, i32)) -> Self {
+        Self {
+            layout: Layout {
+                anchor: Some(anchor),
+                offset,
+                ..self.layout
+            },
+            ..self
+        }
+    }
+
+    /// Places this node `x` and `y` pixels in from the top left of its parent stack's padding.
+    /// Same as anchoring it to [`Anchor::TopLeft`].
+    pub fn at(self, x: u32, y: u32) -> Self {
+        self.anchor(Anchor::TopLeft, (x as i32, y as i32))
+    }
+
+    /// Puts this node in front of siblings with a lower z-index, for drawing and for input.
+    /// Nodes start at 0.
+    pub fn z_index(self, z_index: i32) -> Self {
+        Self { z_index, ..self }
+    }
+
     pub fn push(&mut self, child: impl Into<Self>) {
         self.children.push(child.into());
     }
@@ -184,12 +384,26 @@ impl<'a, Message> Node<'a, Message, Layout> {
 }
 
 impl<'a, Message> Node<'a, Message, CalculatedLayout> {
-    pub fn draw_recursive(&self, frame: &mut [u8], _accum_position: (u32, u32)) {
+    pub fn draw_recursive(
+        &self,
+        frame: &mut Buffer,
+        state_tree: &StateNode,
+        _accum_position: (u32, u32),
+    ) {
         // TODO can we remove mut from self?
-        self.element.draw(frame, self.layout);
-        self.children
-            .iter() // TODO mut bad
-            .for_each(|node| node.draw_recursive(frame, (0, 0)));
+        self.element.draw(frame, state_tree, self.layout);
+        // children can't draw outside of the region their parent was given
+        frame.push_clip(self.layout);
+        frame.push_offset(
+            self.element
+                .scroll_offset(state_tree, self.layout)
+                .unwrap_or_default(),
+        );
+        for i in self.draw_order() {
+            self.children[i].draw_recursive(frame, &state_tree.children[i], (0, 0));
+        }
+        frame.pop_offset();
+        frame.pop_clip();
     }
 
     pub fn get_message(
@@ -197,15 +411,163 @@ impl<'a, Message> Node<'a, Message, CalculatedLayout> {
         state_tree: &mut StateNode,
         input: &crate::Input,
     ) -> Option<Message> {
-        let message = self.element.get_message(state_tree, input, self.layout);
-        if message.is_some() {
-            message
+        // the innermost scroll container under the mouse takes the wheel
+        let wheel_taken_inside = input.wheel_delta != (0, 0)
+            && input
+                .mouse_pos
+                .is_some_and(|pos| self.scrolls_inside(state_tree, pos));
+        let own_input;
+        let input_for_element = if wheel_taken_inside {
+            own_input = crate::Input {
+                wheel_delta: (0, 0),
+                ..input.clone()
+            };
+            &own_input
         } else {
-            self.children
-                .iter_mut()
-                .zip(&mut state_tree.children)
-                .filter_map(|(child, child_state)| child.get_message(child_state, input))
-                .next()
+            input
+        };
+
+        // a focused descendant, like a text input being typed in, sees the keys before its
+        // ancestors do
+        let focus_within = self
+            .children
+            .iter()
+            .zip(&state_tree.children)
+            .any(|(child, child_state)| child.has_focus(child_state));
+        if !focus_within {
+            let message = self
+                .element
+                .get_message(state_tree, input_for_element, self.layout);
+            if message.is_some() {
+                return message;
+            }
+        }
+
+        let message = self.children_get_message(state_tree, input);
+        if message.is_some() || !focus_within {
+            return message;
+        }
+        self.element
+            .get_message(state_tree, input_for_element, self.layout)
+    }
+
+    fn children_get_message(
+        &mut self,
+        state_tree: &mut StateNode,
+        input: &crate::Input,
+    ) -> Option<Message> {
+        let scrolled_input;
+        let input = if self
+            .element
+            .scroll_offset(state_tree, self.layout)
+            .is_some()
+        {
+            // children see the mouse where it is in their own coordinates, and not at all when
+            // it is outside the part of them that is scrolled into view
+            let to_content =
+                |pos: Option<(u32, u32)>| pos.and_then(|pos| self.to_content(state_tree, pos));
+            scrolled_input = crate::Input {
+                mouse_pos: to_content(input.mouse_pos),
+                prev_mouse_pos: to_content(input.prev_mouse_pos),
+                ..input.clone()
+            };
+            &scrolled_input
+        } else {
+            input
+        };
+        // whatever is drawn on top gets the first chance to handle the input. The frontmost
+        // child that takes the mouse hides it from the children behind, whether or not it sends
+        // a message, but they still see the rest of the input, like keys. Layout-only nodes
+        // like an empty column don't take it, so they can be stacked over other content.
+        let mut covered_input = None;
+        for i in self.draw_order().into_iter().rev() {
+            let child_input = covered_input.as_ref().unwrap_or(input);
+            let message = self.children[i].get_message(&mut state_tree.children[i], child_input);
+            if message.is_some() {
+                return message;
+            }
+            let hit = input
+                .mouse_pos
+                .is_some_and(|pos| self.children[i].hit_test(&state_tree.children[i], pos));
+            if hit && covered_input.is_none() {
+                covered_input = Some(crate::Input {
+                    mouse_pos: None,
+                    ..input.clone()
+                });
+            }
         }
+        None
+    }
+
+    /// Where `pos` is among this node's children once its scroll offset is taken into account,
+    /// or `None` if it is outside the part of them that is scrolled into view.
+    fn to_content(&self, state_tree: &StateNode, pos: (u32, u32)) -> Option<(u32, u32)> {
+        match self.element.scroll_offset(state_tree, self.layout) {
+            Some(offset) => self
+                .layout
+                .contains(pos)
+                .then_some((pos.0 + offset.0, pos.1 + offset.1)),
+            None => Some(pos),
+        }
+    }
+
+    /// Whether this node or one of its descendants takes the mouse at `pos`.
+    fn hit_test(&self, state_tree: &StateNode, pos: (u32, u32)) -> bool {
+        self.element.hit_test(state_tree, self.layout, pos)
+            || self.to_content(state_tree, pos).is_some_and(|pos| {
+                self.children
+                    .iter()
+                    .zip(&state_tree.children)
+                    .any(|(child, child_state)| child.hit_test(child_state, pos))
+            })
+    }
+
+    /// Whether one of this node's descendants is a scroll container under `pos`.
+    fn scrolls_inside(&self, state_tree: &StateNode, pos: (u32, u32)) -> bool {
+        self.to_content(state_tree, pos).is_some_and(|pos| {
+            self.children
+                .iter()
+                .zip(&state_tree.children)
+                .any(|(child, child_state)| {
+                    let scrolls = child
+                        .element
+                        .scroll_offset(child_state, child.layout)
+                        .is_some();
+                    (scrolls && child.layout.contains(pos))
+                        || child.scrolls_inside(child_state, pos)
+                })
+        })
+    }
+
+    /// Whether this node or one of its descendants is focused.
+    pub(crate) fn has_focus(&self, state_tree: &StateNode) -> bool {
+        self.element.is_focused(state_tree)
+            || self
+                .children
+                .iter()
+                .zip(&state_tree.children)
+                .any(|(child, child_state)| child.has_focus(child_state))
+    }
+
+    /// Ticks every element in the tree, collecting the messages they send.
+    pub fn tick(
+        &mut self,
+        state_tree: &mut StateNode,
+        elapsed: Duration,
+        messages: &mut Vec<Message>,
+    ) {
+        messages.extend(self.element.tick(state_tree, elapsed));
+        self.children
+            .iter_mut()
+            .zip(&mut state_tree.children)
+            .for_each(|(child, child_state)| child.tick(child_state, elapsed, messages));
+    }
+
+    /// Indices of the children from back to front.
+    fn draw_order(&self) -> Vec<usize> {
+        let mut order: Vec<_> = (0..self.children.len()).collect();
+        // stable, so equal z-indices keep their order
+        order.sort_by_key(|i| self.children[*i].z_index);
+        order
     }
 }
@@ -0,0 +1,127 @@
+use icecube::layout::{Align, CalculatedLayout, Justify, Layout, Length};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+
+fn fixed(w: u32, h: u32) -> Node<'static, (), Layout> {
+    Node::new(Quad::new()).width(w).height(h)
+}
+
+/// Lays out `children` in a root whose inner area is 100x50 starting at (1, 1)
+fn positions(root: Node<'static, (), Layout>) -> Vec<CalculatedLayout> {
+    root.calculate_layout()
+        .children
+        .into_iter()
+        .map(|c| c.layout)
+        .collect()
+}
+
+fn root() -> Node<'static, (), Layout> {
+    Node::root_node(102, 52)
+}
+
+fn xs(layouts: &[CalculatedLayout]) -> Vec<u32> {
+    layouts.iter().map(|l| l.x).collect()
+}
+
+fn ys(layouts: &[CalculatedLayout]) -> Vec<u32> {
+    layouts.iter().map(|l| l.y).collect()
+}
+
+fn row_of_three(justify: Justify) -> Vec<u32> {
+    let mut root = root().row().spacing(2).justify(justify);
+    root.push(fixed(10, 10));
+    root.push(fixed(20, 10));
+    root.push(fixed(10, 10));
+    xs(&positions(root))
+}
+
+#[test]
+fn justify_row() {
+    // 40px of children and 4px of spacing leaves 56px free
+    assert_eq!(row_of_three(Justify::Start), vec![1, 13, 35]);
+    assert_eq!(row_of_three(Justify::Center), vec![29, 41, 63]);
+    assert_eq!(row_of_three(Justify::End), vec![57, 69, 91]);
+    assert_eq!(row_of_three(Justify::SpaceBetween), vec![1, 41, 91]);
+    // pushed along by 56 * 1/6, 56 * 3/6 and 56 * 5/6 (rounded down)
+    assert_eq!(row_of_three(Justify::SpaceAround), vec![10, 41, 81]);
+}
+
+#[test]
+fn space_between_single_child_stays_at_start() {
+    let mut root = root().row().justify(Justify::SpaceBetween);
+    root.push(fixed(10, 10));
+    assert_eq!(xs(&positions(root)), vec![1]);
+}
+
+#[test]
+fn justify_column() {
+    let mut root = root().column().justify(Justify::End);
+    root.push(fixed(10, 10));
+    root.push(fixed(10, 20));
+    assert_eq!(ys(&positions(root)), vec![21, 31]);
+}
+
+#[test]
+fn align_row() {
+    let aligned = |align| {
+        let mut root = root().row().align(align);
+        root.push(fixed(10, 10));
+        root.push(fixed(10, 21));
+        ys(&positions(root))
+    };
+    assert_eq!(aligned(Align::Start), vec![1, 1]);
+    assert_eq!(aligned(Align::Center), vec![21, 15]);
+    assert_eq!(aligned(Align::End), vec![41, 30]);
+}
+
+#[test]
+fn align_column() {
+    let mut root = root().column().align(Align::Center);
+    root.push(fixed(10, 10));
+    root.push(fixed(30, 10));
+    assert_eq!(xs(&positions(root)), vec![46, 36]);
+}
+
+#[test]
+fn stack_justifies_horizontally_and_aligns_vertically() {
+    let mut root = root().stack().justify(Justify::Center).align(Align::End);
+    root.push(fixed(10, 10));
+    root.push(fixed(30, 20));
+    let layouts = positions(root);
+    assert_eq!(xs(&layouts), vec![46, 36]);
+    assert_eq!(ys(&layouts), vec![41, 31]);
+}
+
+#[test]
+fn stretch_fills_shrinking_children_only() {
+    let mut root = root().row().align(Align::Stretch);
+    root.push(Node::new(Quad::new()).width(10).height(Length::Shrink));
+    root.push(fixed(10, 10));
+    let layouts = positions(root);
+    assert_eq!(layouts[0].h, 50);
+    assert_eq!(layouts[1].h, 10);
+}
+
+#[test]
+fn stretch_in_column_fills_width() {
+    let mut root = root().column().align(Align::Stretch);
+    let mut label = Node::new(Quad::new()).width(Length::Shrink).height(10);
+    label.push(fixed(20, 10));
+    root.push(label);
+    assert_eq!(positions(root)[0].w, 100);
+}
+
+#[test]
+fn centered_label_in_button() {
+    let mut button = Node::new(Quad::new())
+        .width(40)
+        .height(20)
+        .justify(Justify::Center)
+        .align(Align::Center);
+    button.push(fixed(10, 6));
+    let mut root = root();
+    root.push(button);
+
+    let label = root.calculate_layout().children[0].children[0].layout;
+    assert_eq!((label.x, label.y), (1 + 15, 1 + 7));
+}
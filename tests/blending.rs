@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use icecube::buffer::Buffer;
+use icecube::headless::Headless;
+use icecube::image::Image;
+use icecube::layout::{Layout, Length};
+use icecube::palette::{blend, with_coverage, Color, BLUE_DARK, MAIN_LIGHT};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+use icecube::Input;
+use image::RgbaImage;
+
+const BLACK: Color = [0, 0, 0, 255];
+const HALF_WHITE: Color = [255, 255, 255, 128];
+
+fn render<'a>(view: impl Fn(&()) -> Node<'a, (), Layout>) -> RgbaImage {
+    let mut app = Headless::new((), |_, _| {}, view, 16, 16, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    app.to_image()
+}
+
+#[test]
+fn opaque_and_transparent() {
+    assert_eq!(blend(BLACK, BLUE_DARK), BLUE_DARK);
+    assert_eq!(blend(BLUE_DARK, [255, 0, 0, 0]), BLUE_DARK);
+}
+
+#[test]
+fn source_over() {
+    assert_eq!(blend(BLACK, HALF_WHITE), [128, 128, 128, 255]);
+    // nothing underneath, so the color keeps its own alpha
+    assert_eq!(blend([0; 4], [200, 100, 50, 128]), [200, 100, 50, 128]);
+    // two half transparent layers cover three quarters
+    assert_eq!(blend([0, 0, 0, 128], HALF_WHITE), [170, 170, 170, 192]);
+}
+
+#[test]
+fn coverage_scales_alpha() {
+    assert_eq!(with_coverage(BLUE_DARK, 255), BLUE_DARK);
+    assert_eq!(with_coverage([1, 2, 3, 255], 51), [1, 2, 3, 51]);
+    assert_eq!(with_coverage([1, 2, 3, 128], 0), [1, 2, 3, 0]);
+}
+
+#[test]
+fn buffer_blends() {
+    let mut data = [0, 0, 0, 255].repeat(4);
+    let mut frame = Buffer::new(&mut data, 2, 2);
+    frame.set_pixel(0, 0, HALF_WHITE);
+    frame.set_pixel(1, 0, [255, 0, 0, 0]);
+    frame.set_pixel(0, 1, BLUE_DARK);
+    assert_eq!(&data[0..4], &[128, 128, 128, 255]);
+    assert_eq!(&data[4..8], &BLACK);
+    assert_eq!(&data[8..12], &BLUE_DARK);
+}
+
+#[test]
+fn translucent_quad_over_content() {
+    let frame = render(|_| {
+        let mut parent = Node::new(Quad::new().fill(BLACK))
+            .stack()
+            .width(8)
+            .height(8);
+        parent.push(
+            Node::new(Quad::new().fill(HALF_WHITE))
+                .width(Length::Grow)
+                .height(Length::Grow),
+        );
+        parent
+    });
+    assert_eq!(frame.get_pixel(4, 4).0, [128, 128, 128, 255]);
+}
+
+#[test]
+fn transparent_image_pixels_let_the_background_through() {
+    let frame = render(|_| {
+        let image = Image::new(vec![BLUE_DARK, [0; 4], HALF_WHITE, BLUE_DARK], 2, 2);
+        let mut parent = Node::new(Quad::new().fill(BLACK)).width(4).height(4);
+        parent.push(Node::new(image));
+        parent
+    });
+    // the root node has 1px of padding
+    assert_eq!(frame.get_pixel(1, 1).0, BLUE_DARK);
+    assert_eq!(frame.get_pixel(2, 1).0, BLACK);
+    assert_eq!(frame.get_pixel(1, 2).0, [128, 128, 128, 255]);
+}
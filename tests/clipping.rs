@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::layout::{Layout, Length};
+use icecube::palette::{BLUE_DARK, MAIN_LIGHT, RED_DARK};
+use icecube::quad::Quad;
+use icecube::text::Text;
+use icecube::tree::Node;
+use icecube::Input;
+use image::RgbaImage;
+
+fn render<'a>(view: impl Fn(&()) -> Node<'a, (), Layout>) -> RgbaImage {
+    let mut app = Headless::new((), |_, _| {}, view, 64, 32, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    app.to_image()
+}
+
+#[test]
+fn child_is_clipped_to_parent() {
+    let frame = render(|_| {
+        let mut parent = Node::new(Quad::new().fill(BLUE_DARK))
+            .width(Length::Fixed(10))
+            .height(Length::Fixed(10));
+        parent.push(
+            Node::new(Quad::new().fill(RED_DARK))
+                .width(Length::Fixed(30))
+                .height(Length::Fixed(30)),
+        );
+        parent
+    });
+
+    // the root node has 1px of padding
+    assert_eq!(frame.get_pixel(1, 1).0, RED_DARK);
+    assert_eq!(frame.get_pixel(10, 10).0, RED_DARK);
+    assert_eq!(frame.get_pixel(11, 1).0, MAIN_LIGHT);
+    assert_eq!(frame.get_pixel(1, 11).0, MAIN_LIGHT);
+    assert_eq!(frame.get_pixel(25, 25).0, MAIN_LIGHT);
+}
+
+#[test]
+fn clip_applies_to_grandchildren() {
+    let frame = render(|_| {
+        let mut grandparent = Node::new(Quad::new())
+            .width(Length::Fixed(10))
+            .height(Length::Fixed(10));
+        let mut parent = Node::new(Quad::new())
+            .width(Length::Fixed(20))
+            .height(Length::Fixed(20));
+        parent.push(
+            Node::new(Quad::new().fill(RED_DARK))
+                .width(Length::Fixed(30))
+                .height(Length::Fixed(30)),
+        );
+        grandparent.push(parent);
+        grandparent
+    });
+
+    assert_eq!(frame.get_pixel(10, 10).0, RED_DARK);
+    assert_eq!(frame.get_pixel(15, 5).0, MAIN_LIGHT);
+}
+
+#[test]
+fn overflowing_text_stays_inside_its_parent() {
+    let frame = render(|_| {
+        let mut parent = Node::new(Quad::new())
+            .width(Length::Fixed(12))
+            .height(Length::Fixed(4));
+        parent.push(
+            Node::new(Text::new("overflow overflow overflow".to_string()).with_color(RED_DARK))
+                .width(Length::Grow),
+        );
+        parent
+    });
+
+    for y in 0..32 {
+        for x in 0..64 {
+            if !(1..13).contains(&x) || !(1..5).contains(&y) {
+                assert_eq!(
+                    frame.get_pixel(x, y).0,
+                    MAIN_LIGHT,
+                    "pixel at ({x}, {y}) was drawn"
+                );
+            }
+        }
+    }
+}
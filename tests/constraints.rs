@@ -0,0 +1,85 @@
+use icecube::layout::{CalculatedLayout, Layout, Length};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+
+fn grow() -> Node<'static, (), Layout> {
+    Node::new(Quad::new())
+        .width(Length::Grow)
+        .height(Length::Grow)
+}
+
+fn positions(root: Node<'static, (), Layout>) -> Vec<CalculatedLayout> {
+    root.calculate_layout()
+        .children
+        .into_iter()
+        .map(|c| c.layout)
+        .collect()
+}
+
+fn widths(layouts: &[CalculatedLayout]) -> Vec<u32> {
+    layouts.iter().map(|l| l.w).collect()
+}
+
+/// Root whose inner area is 100x50 starting at (1, 1)
+fn root() -> Node<'static, (), Layout> {
+    Node::root_node(102, 52)
+}
+
+#[test]
+fn max_width_gives_space_to_siblings() {
+    let mut root = root().row();
+    root.push(grow().max_width(20));
+    root.push(grow());
+    assert_eq!(widths(&positions(root)), vec![20, 80]);
+}
+
+#[test]
+fn clamping_cascades() {
+    let mut root = root().row();
+    root.push(grow().max_width(10));
+    root.push(grow().max_width(30));
+    root.push(grow());
+    // the first split of 33 each is too much for both clamped children
+    assert_eq!(widths(&positions(root)), vec![10, 30, 60]);
+}
+
+#[test]
+fn min_width_on_growing_child() {
+    let mut root = root().row();
+    root.push(grow().min_width(70));
+    root.push(grow());
+    root.push(grow());
+    // like a minimum from content, the share of leftover space comes on top
+    assert_eq!(widths(&positions(root)), vec![80, 10, 10]);
+}
+
+#[test]
+fn min_height_on_shrinking_child() {
+    let mut root = root().column();
+    root.push(Node::new(Quad::new()).width(10).min_height(40));
+    root.push(grow());
+    let heights: Vec<_> = positions(root).iter().map(|l| l.h).collect();
+    assert_eq!(heights, vec![40, 10]);
+}
+
+#[test]
+fn max_height_on_cross_axis() {
+    let mut root = root().row();
+    root.push(grow().max_height(20));
+    root.push(
+        Node::new(Quad::new())
+            .width(10)
+            .height(Length::Percent(100))
+            .max_height(30),
+    );
+    let heights: Vec<_> = positions(root).iter().map(|l| l.h).collect();
+    assert_eq!(heights, vec![20, 30]);
+}
+
+#[test]
+fn fixed_length_is_clamped() {
+    let mut root = root().row();
+    root.push(Node::new(Quad::new()).width(200).max_width(60).height(10));
+    root.push(grow());
+    assert_eq!(widths(&positions(root)), vec![60, 40]);
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use icecube::font::{BdfFont, Font, FontStack, FontType, ImageFont, ImageFontMetrics};
+use icecube::headless::Headless;
+use icecube::layout::Layout;
+use icecube::palette::{MAIN_LIGHT, RED_DARK};
+use icecube::text::Text;
+use icecube::tree::Node;
+use icecube::Input;
+use image::GrayImage;
+
+/// An image font of 2x2 cells in a single row from `first` to `last`, lighting one pixel of each
+/// listed character's cell
+fn font(first: char, last: char, lit: &[(char, u32, u32)]) -> &'static FontType {
+    let cells = last as usize - first as usize + 1;
+    let mut image = GrayImage::new(cells as u32 * 3 - 1, 2);
+    for &(c, x, y) in lit {
+        let cell = (c as u32 - first as u32) * 3;
+        image.put_pixel(cell + x, y, [255].into());
+    }
+    let metrics = ImageFontMetrics {
+        first_char: first,
+        last_char: last,
+        character_width: 2,
+        character_height: 2,
+        characters_per_row: cells,
+        x_spacing: 1,
+        y_spacing: 1,
+        x_margin: 0,
+        y_margin: 0,
+    };
+    Box::leak(Box::new(FontType::Image(ImageFont::new(image, metrics))))
+}
+
+/// 'A' lights its top left pixel, 'B' its bottom right
+fn primary() -> &'static FontType {
+    font('A', 'B', &[('A', 0, 0), ('B', 1, 1)])
+}
+
+/// Has '?' (top right), its own 'A' (bottom right) and 'C' (bottom left)
+fn fallback() -> &'static FontType {
+    font('?', 'C', &[('?', 1, 0), ('A', 1, 1), ('C', 0, 1)])
+}
+
+/// The lit pixels of the first two characters of `text`, inside the root's padding
+fn lit(text: impl Fn() -> Text + 'static) -> Vec<(u32, u32)> {
+    let view =
+        move |_: &()| -> Node<'static, (), Layout> { Node::new(text().with_color(RED_DARK)) };
+    let mut app = Headless::new((), |_, _| {}, view, 16, 16, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    (1..6)
+        .flat_map(|y| (1..6).map(move |x| (x, y)))
+        .filter(|(x, y)| app.pixel(*x, *y) == RED_DARK)
+        .collect()
+}
+
+#[test]
+fn each_character_uses_the_first_font_that_has_it() {
+    let (primary, fallback) = (primary(), fallback());
+    let shown = lit(move || {
+        Text::new("AC".into())
+            .with_font(primary)
+            .with_fallback(fallback)
+    });
+    // the primary's 'A', then the fallback's 'C' a cell and a pixel along
+    assert_eq!(shown, vec![(1, 1), (4, 2)]);
+}
+
+#[test]
+fn missing_everywhere_uses_a_fallback_character() {
+    let (primary, fallback) = (primary(), fallback());
+    let shown = lit(move || {
+        Text::new("AZ".into())
+            .with_font(primary)
+            .with_fallback(fallback)
+    });
+    // the primary has no '?', so it comes from the fallback
+    assert_eq!(shown, vec![(1, 1), (5, 1)]);
+}
+
+#[test]
+fn replacement_box_is_the_last_resort() {
+    let primary = primary();
+    let shown = lit(move || Text::new("Zé".into()).with_font(primary));
+    assert_eq!(
+        shown,
+        vec![
+            (1, 1),
+            (2, 1),
+            (4, 1),
+            (5, 1),
+            (1, 2),
+            (2, 2),
+            (4, 2),
+            (5, 2)
+        ]
+    );
+}
+
+#[test]
+fn stack_reports_what_it_can_draw() {
+    let fallbacks = [fallback()];
+    let stack = FontStack::new(primary(), &fallbacks);
+    assert!(stack.has_glyph('A') && stack.has_glyph('C') && stack.has_glyph('?'));
+    assert!(!stack.has_glyph('Z'));
+    assert_eq!(stack.height(), 2);
+}
+
+#[test]
+fn missing_bdf_glyphs_dont_panic() {
+    let font = BdfFont::from_bytes(
+        b"STARTFONT 2.1
+FONT test
+SIZE 8 75 75
+FONTBOUNDINGBOX 4 8 0 -2
+CHARS 1
+STARTCHAR A
+ENCODING 65
+SWIDTH 500 0
+DWIDTH 4 0
+BBX 3 2 0 0
+BITMAP
+E0
+A0
+ENDCHAR
+ENDFONT
+",
+    )
+    .unwrap();
+    assert!(!font.has_glyph('é'));
+    // neither 'é' nor '?', so the replacement box
+    assert_eq!(font.glyph_width('é'), 4);
+    // and spaces come from the space width, even without a space glyph
+    assert!(font.has_glyph(' '));
+    let font: &'static FontType = Box::leak(Box::new(FontType::Bdf(font)));
+    lit(move || Text::new("Aé é".into()).with_font(font));
+}
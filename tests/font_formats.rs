@@ -0,0 +1,231 @@
+use std::io::Cursor;
+
+use icecube::buffer::Buffer;
+use icecube::font::{BmFont, Font, FontError, PsfFont};
+use icecube::palette::RED_DARK;
+use image::{ImageFormat, RgbaImage};
+
+/// The pixels `font` lights drawing `character` at (2, 2), and how far it advances
+fn drawn(font: &impl Font, character: char) -> (Vec<(usize, usize)>, usize) {
+    let mut data = vec![0; 12 * 12 * 4];
+    let advance = font.draw_character(
+        &mut Buffer::new(&mut data, 12, 12),
+        2,
+        2,
+        character,
+        RED_DARK,
+    );
+    let lit = data
+        .chunks_exact(4)
+        .enumerate()
+        .filter(|(_, pixel)| *pixel == RED_DARK)
+        .map(|(i, _)| (i % 12, i / 12))
+        .collect();
+    (lit, advance)
+}
+
+/// Code point, x, y, width, height, x offset, y offset and advance of a glyph
+type Glyph = (u32, u16, u16, u16, u16, i16, i16, i16);
+
+const GLYPHS: [Glyph; 3] = [
+    ('A' as u32, 0, 0, 2, 2, 0, 1, 3),
+    ('V' as u32, 2, 0, 2, 2, -1, 0, 4),
+    ('?' as u32, 4, 0, 2, 2, 0, 0, 3),
+];
+
+/// A 6x2 page lighting one pixel in each glyph: 'A' its top left, 'V' its bottom right and '?'
+/// its top left. Next to 'A' is a white pixel that is fully transparent.
+fn page() -> Vec<u8> {
+    let mut image = RgbaImage::new(6, 2);
+    for (x, y) in [(0, 0), (3, 1), (4, 0)] {
+        image.put_pixel(x, y, [255, 255, 255, 255].into());
+    }
+    image.put_pixel(1, 0, [255, 255, 255, 0].into());
+    let mut bytes = Cursor::new(vec![]);
+    image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+    bytes.into_inner()
+}
+
+fn text_fnt() -> String {
+    let mut fnt = String::from(
+        "info face=\"Test Font\" size=4 bold=0 padding=0,0,0,0\n\
+         common lineHeight=5 base=4 scaleW=6 scaleH=2 pages=1 packed=0\n\
+         page id=0 file=\"test_0.png\"\n\
+         chars count=3\n",
+    );
+    for (id, x, y, w, h, xo, yo, advance) in GLYPHS {
+        fnt += &format!(
+            "char id={id} x={x} y={y} width={w} height={h} xoffset={xo} yoffset={yo} \
+             xadvance={advance} page=0 chnl=15\n"
+        );
+    }
+    fnt + "kernings count=1\nkerning first=65 second=86 amount=-1\n"
+}
+
+fn binary_fnt() -> Vec<u8> {
+    let block = |kind: u8, data: Vec<u8>| {
+        let mut block = vec![kind];
+        block.extend((data.len() as u32).to_le_bytes());
+        block.extend(data);
+        block
+    };
+    let common = [5u16, 4, 6, 2, 1]
+        .iter()
+        .flat_map(|n| n.to_le_bytes())
+        .chain([0; 5])
+        .collect();
+    let chars = GLYPHS
+        .iter()
+        .flat_map(|&(id, x, y, w, h, xo, yo, advance)| {
+            let mut glyph = id.to_le_bytes().to_vec();
+            for n in [x, y, w, h, xo as u16, yo as u16, advance as u16] {
+                glyph.extend(n.to_le_bytes());
+            }
+            glyph.extend([0, 15]);
+            glyph
+        })
+        .collect();
+    let kerning = [65u32, 86]
+        .iter()
+        .flat_map(|c| c.to_le_bytes())
+        .chain((-1i16).to_le_bytes())
+        .collect();
+    [
+        b"BMF\x03".to_vec(),
+        block(1, vec![0; 14]),
+        block(2, common),
+        block(3, b"test_0.png\0".to_vec()),
+        block(4, chars),
+        block(5, kerning),
+    ]
+    .concat()
+}
+
+fn check_bmfont(font: &BmFont) {
+    assert_eq!(font.height(), 5);
+    assert_eq!(font.width(), 4);
+    assert!(font.has_glyph('A') && !font.has_glyph('Z'));
+    assert_eq!(font.kerning('A', 'V'), -1);
+    assert_eq!(font.kerning('V', 'A'), 0);
+    // offsets move the glyph away from the pen, and the transparent pixel isn't drawn
+    assert_eq!(drawn(font, 'A'), (vec![(2, 3)], 3));
+    assert_eq!(drawn(font, 'V'), (vec![(2, 3)], 4));
+    // missing characters are drawn as '?'
+    assert_eq!(drawn(font, 'Z'), (vec![(2, 2)], 3));
+}
+
+#[test]
+fn bmfont_text_descriptor() {
+    check_bmfont(&BmFont::from_bytes(text_fnt().as_bytes(), &[&page()]).unwrap());
+}
+
+#[test]
+fn bmfont_binary_descriptor() {
+    check_bmfont(&BmFont::from_bytes(&binary_fnt(), &[&page()]).unwrap());
+}
+
+#[test]
+fn bmfont_pages_are_found_next_to_the_descriptor() {
+    let directory = std::env::temp_dir().join("icecube_font_formats");
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("test.fnt"), text_fnt()).unwrap();
+    std::fs::write(directory.join("test_0.png"), page()).unwrap();
+    let font = BmFont::open(directory.join("test.fnt"));
+    std::fs::remove_dir_all(&directory).unwrap();
+    check_bmfont(&font.unwrap());
+}
+
+#[test]
+fn bmfont_errors() {
+    // no page to hold the glyphs
+    assert!(matches!(
+        BmFont::from_bytes(text_fnt().as_bytes(), &[]),
+        Err(FontError::Malformed(_))
+    ));
+    assert!(matches!(
+        BmFont::from_bytes(b"BMF\x02", &[&page()]),
+        Err(FontError::Malformed(_))
+    ));
+    assert!(matches!(
+        BmFont::from_bytes(text_fnt().as_bytes(), &[b"not an image"]),
+        Err(FontError::Image(_))
+    ));
+    // negative positions and sizes past the end of the page
+    for glyph in ["x=-1 y=0 width=2", "x=1 y=0 width=4294967295"] {
+        let fnt = text_fnt()
+            + &format!("char id=66 {glyph} height=2 xoffset=0 yoffset=0 xadvance=3 page=0\n");
+        assert!(matches!(
+            BmFont::from_bytes(fnt.as_bytes(), &[&page()]),
+            Err(FontError::Malformed(_))
+        ));
+    }
+    assert!(matches!(
+        BmFont::open("does/not/exist.fnt"),
+        Err(FontError::Io(_))
+    ));
+}
+
+#[test]
+fn psf1_with_unicode_table() {
+    let mut bytes = vec![0x36, 0x04, 0x02, 2];
+    // 256 glyphs 8x2: the first lights its top left pixel, the second its bottom right
+    let mut glyphs = vec![0; 256 * 2];
+    glyphs[0] = 0x80;
+    glyphs[3] = 0x01;
+    bytes.extend(glyphs);
+    // 'A' is the first glyph, 'é' the second, which also has a sequence for "e" and an accent
+    let mut table = vec![0x41, 0xFFFF, 0xE9, 0xFFFE, 0x65, 0x301, 0xFFFF];
+    table.extend([0xFFFF; 254]);
+    bytes.extend(table.iter().flat_map(|c: &u16| c.to_le_bytes()));
+
+    let font = PsfFont::from_bytes(&bytes).unwrap();
+    assert_eq!((font.width(), font.height()), (8, 2));
+    assert!(font.has_glyph('A') && font.has_glyph('é') && !font.has_glyph('e'));
+    assert_eq!(drawn(&font, 'A'), (vec![(2, 2)], 8));
+    assert_eq!(drawn(&font, 'é'), (vec![(9, 3)], 8));
+    // no '?' either, so the replacement box fills the whole cell
+    let (lit, _) = drawn(&font, 'Z');
+    assert_eq!(lit.len(), 16);
+}
+
+fn psf2(flags: u32, glyph_count: u32, glyph_bytes: u32, height: u32, width: u32) -> Vec<u8> {
+    let mut bytes = vec![0x72, 0xb5, 0x4a, 0x86];
+    for field in [0, 32, flags, glyph_count, glyph_bytes, height, width] {
+        bytes.extend(field.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn psf2_without_unicode_table_uses_code_points() {
+    // 128 glyphs 10x2, with rows of two bytes
+    let mut bytes = psf2(0, 128, 4, 2, 10);
+    let mut glyphs = vec![0; 128 * 4];
+    glyphs[65 * 4..66 * 4].copy_from_slice(&[0x00, 0x40, 0x80, 0x00]);
+    bytes.extend(glyphs);
+
+    let font = PsfFont::from_bytes(&bytes).unwrap();
+    assert!(font.has_glyph('A') && !font.has_glyph('é'));
+    assert_eq!(drawn(&font, 'A'), (vec![(11, 2), (2, 3)], 10));
+}
+
+#[test]
+fn psf2_with_unicode_table() {
+    let mut bytes = psf2(1, 2, 1, 1, 3);
+    bytes.extend([0b1000_0000, 0b0010_0000]);
+    bytes.extend("Ж".as_bytes());
+    bytes.push(0xFF);
+    bytes.extend(b"x\xFEy\xCC\x81\xFF");
+
+    let font = PsfFont::from_bytes(&bytes).unwrap();
+    assert!(font.has_glyph('Ж') && font.has_glyph('x') && !font.has_glyph('y'));
+    assert_eq!(drawn(&font, 'Ж'), (vec![(2, 2)], 3));
+    assert_eq!(drawn(&font, 'x'), (vec![(4, 2)], 3));
+}
+
+#[test]
+fn psf_errors() {
+    assert!(PsfFont::from_bytes(b"not a font").is_err());
+    // says it has 128 glyphs but has none
+    assert!(PsfFont::from_bytes(&psf2(0, 128, 4, 2, 10)).is_err());
+}
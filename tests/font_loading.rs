@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use icecube::font::{BdfFont, Font, FontType, ImageFont, ImageFontMetrics};
+use icecube::headless::Headless;
+use icecube::layout::Layout;
+use icecube::palette::{MAIN_LIGHT, RED_DARK};
+use icecube::text::Text;
+use icecube::tree::Node;
+use icecube::Input;
+use image::GrayImage;
+
+/// A BDF font with a space and an 'A', with its properties left out when `properties` is false
+fn bdf(properties: bool) -> String {
+    let properties = match properties {
+        true => "STARTPROPERTIES 2\nFONT_ASCENT 6\nFONT_DESCENT 2\nENDPROPERTIES\n",
+        false => "",
+    };
+    format!(
+        "STARTFONT 2.1
+FONT test
+SIZE 8 75 75
+FONTBOUNDINGBOX 4 9 0 -2
+{properties}CHARS 2
+STARTCHAR space
+ENCODING 32
+SWIDTH 500 0
+DWIDTH 3 0
+BBX 1 1 0 0
+BITMAP
+00
+ENDCHAR
+STARTCHAR A
+ENCODING 65
+SWIDTH 500 0
+DWIDTH 4 0
+BBX 3 2 0 0
+BITMAP
+E0
+A0
+ENDCHAR
+ENDFONT
+"
+    )
+}
+
+/// 'A' and 'B' in 2x2 cells one pixel apart. 'A' lights its top left pixel, 'B' its bottom
+/// right.
+fn atlas() -> (GrayImage, ImageFontMetrics) {
+    let mut image = GrayImage::new(5, 2);
+    image.put_pixel(0, 0, [255].into());
+    image.put_pixel(4, 1, [255].into());
+    let metrics = ImageFontMetrics {
+        first_char: 'A',
+        last_char: 'B',
+        character_width: 2,
+        character_height: 2,
+        characters_per_row: 2,
+        x_spacing: 1,
+        y_spacing: 1,
+        x_margin: 0,
+        y_margin: 0,
+    };
+    (image, metrics)
+}
+
+#[test]
+fn bdf_metrics_come_from_the_file() {
+    let font = BdfFont::from_bytes(bdf(true).as_bytes()).unwrap();
+    assert_eq!(font.height(), 8);
+    assert_eq!(font.glyph_width(' '), 3);
+    assert_eq!(font.glyph_width('A'), 3);
+    assert_eq!(font.width(), 4);
+
+    // without ascent and descent, the bounding box has to do
+    let font = BdfFont::from_bytes(bdf(false).as_bytes()).unwrap();
+    assert_eq!(font.height(), 9);
+}
+
+#[test]
+fn bdf_metrics_can_be_overridden() {
+    let font = BdfFont::from_bytes(bdf(true).as_bytes())
+        .unwrap()
+        .space_width(5)
+        .line_height(12);
+    assert_eq!((font.glyph_width(' '), font.height()), (5, 12));
+}
+
+#[test]
+fn bdf_files() {
+    let path = std::env::temp_dir().join("icecube_font_loading.bdf");
+    std::fs::write(&path, bdf(true)).unwrap();
+    let font = BdfFont::open(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(font.unwrap().height(), 8);
+    assert!(BdfFont::open("does/not/exist.bdf").is_err());
+}
+
+#[test]
+fn bdf_glyphs_can_be_taller_than_the_line() {
+    // 'B' is 10px tall and reaches 3px below the baseline, in a font with 8px lines
+    let bdf = "STARTFONT 2.1
+FONT tall
+SIZE 8 75 75
+FONTBOUNDINGBOX 1 10 0 -3
+STARTPROPERTIES 2
+FONT_ASCENT 6
+FONT_DESCENT 2
+ENDPROPERTIES
+CHARS 1
+STARTCHAR B
+ENCODING 66
+DWIDTH 2 0
+BBX 1 10 0 -3
+BITMAP
+80
+80
+80
+80
+80
+80
+80
+80
+80
+80
+ENDCHAR
+ENDFONT
+";
+    let font: &'static FontType = Box::leak(Box::new(FontType::Bdf(
+        BdfFont::from_bytes(bdf.as_bytes()).unwrap(),
+    )));
+    assert_eq!(font.height(), 8);
+    let view = move |_: &()| -> Node<'static, (), Layout> {
+        Node::new(Text::new("B".into()).with_font(font).with_color(RED_DARK))
+    };
+    let mut app = Headless::new((), |_, _| {}, view, 16, 16, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    // the line starts at y = 1 inside the root's padding, and the glyph sticks out one pixel
+    // above it and one below
+    let lit: Vec<_> = (0..16).filter(|y| app.pixel(1, *y) == RED_DARK).collect();
+    assert_eq!(lit, (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn image_font_draws_from_its_cells() {
+    let (image, metrics) = atlas();
+    let font: &'static FontType =
+        Box::leak(Box::new(FontType::Image(ImageFont::new(image, metrics))));
+    let view = move |_: &()| -> Node<'static, (), Layout> {
+        Node::new(Text::new("AB".into()).with_font(font).with_color(RED_DARK))
+    };
+    let mut app = Headless::new((), |_, _| {}, view, 16, 16, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    // inside the root's padding, with a pixel between characters
+    let lit: Vec<_> = (1..8)
+        .flat_map(|x| (1..3).map(move |y| (x, y)))
+        .filter(|(x, y)| app.pixel(*x, *y) == RED_DARK)
+        .collect();
+    assert_eq!(lit, vec![(1, 1), (5, 2)]);
+}
+
+#[test]
+fn image_font_atlas_must_fit_its_metrics() {
+    let (image, metrics) = atlas();
+    let mut encoded = std::io::Cursor::new(vec![]);
+    image
+        .write_to(&mut encoded, image::ImageFormat::Png)
+        .unwrap();
+    let encoded = encoded.into_inner();
+    assert!(ImageFont::from_bytes(&encoded, metrics).is_ok());
+    let too_many = ImageFontMetrics {
+        last_char: 'C',
+        ..metrics
+    };
+    assert!(ImageFont::from_bytes(&encoded, too_many).is_err());
+}
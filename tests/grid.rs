@@ -0,0 +1,141 @@
+use icecube::layout::{Align, Axis, CalculatedLayout, Justify, Layout, LayoutError, Length};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+
+fn fixed(w: u32, h: u32) -> Node<'static, (), Layout> {
+    Node::new(Quad::new()).width(w).height(h)
+}
+
+fn grow() -> Node<'static, (), Layout> {
+    Node::new(Quad::new())
+        .width(Length::Grow)
+        .height(Length::Grow)
+}
+
+fn grid(
+    columns: impl IntoIterator<Item = Length>,
+    rows: impl IntoIterator<Item = Length>,
+) -> Node<'static, (), Layout> {
+    Node::new(Quad::new())
+        .grid(columns, rows)
+        .width(Length::Grow)
+        .height(Length::Grow)
+}
+
+/// Lays `grid` out in a root whose inner area is 100x50 starting at (1, 1), returning where
+/// its children ended up
+fn cells(grid: Node<'static, (), Layout>) -> Vec<CalculatedLayout> {
+    let mut root = Node::root_node(102, 52);
+    root.push(grid);
+    root.calculate_layout()
+        .children
+        .remove(0)
+        .children
+        .into_iter()
+        .map(|c| c.layout)
+        .collect()
+}
+
+fn xywh(layouts: &[CalculatedLayout]) -> Vec<(u32, u32, u32, u32)> {
+    layouts.iter().map(|l| (l.x, l.y, l.w, l.h)).collect()
+}
+
+#[test]
+fn columns_share_widths() {
+    let mut grid = grid([Length::Shrink; 2], [Length::Shrink; 2]).spacing(2);
+    grid.push(fixed(10, 5));
+    grid.push(fixed(20, 5));
+    grid.push(fixed(30, 5));
+    grid.push(fixed(5, 5));
+    assert_eq!(
+        xywh(&cells(grid)),
+        vec![(1, 1, 10, 5), (33, 1, 20, 5), (1, 8, 30, 5), (33, 8, 5, 5)]
+    );
+}
+
+#[test]
+fn growing_tracks() {
+    let mut grid = grid(
+        [Length::Fixed(20), Length::Grow, Length::FillPortion(2)],
+        [Length::Grow],
+    );
+    for _ in 0..3 {
+        grid.push(grow());
+    }
+    assert_eq!(
+        xywh(&cells(grid)),
+        vec![(1, 1, 20, 50), (21, 1, 26, 50), (47, 1, 54, 50)]
+    );
+}
+
+#[test]
+fn spanning_children() {
+    let mut grid = grid([Length::Fixed(10); 3], [Length::Fixed(10); 3]).spacing(1);
+    grid.push(grow().span(2, 1));
+    grid.push(grow());
+    grid.push(grow().span(1, 2));
+    assert_eq!(
+        xywh(&cells(grid)),
+        vec![(1, 1, 21, 10), (23, 1, 10, 10), (1, 12, 10, 21)]
+    );
+}
+
+#[test]
+fn explicit_cells_are_kept_free() {
+    let mut grid = grid([Length::Fixed(10); 2], [Length::Fixed(10); 2]);
+    grid.push(fixed(10, 10));
+    grid.push(fixed(10, 10));
+    grid.push(fixed(10, 10).cell(1, 0));
+    let positions: Vec<_> = cells(grid).iter().map(|l| (l.x, l.y)).collect();
+    assert_eq!(positions, vec![(1, 1), (1, 11), (11, 1)]);
+}
+
+#[test]
+fn spanning_child_widens_its_tracks() {
+    let mut grid = grid([Length::Shrink; 2], [Length::Shrink; 2]);
+    grid.push(fixed(10, 10));
+    grid.push(fixed(10, 10));
+    grid.push(fixed(40, 10).span(2, 1));
+    let positions: Vec<_> = cells(grid).iter().map(|l| l.x).collect();
+    assert_eq!(positions, vec![1, 21, 1]);
+}
+
+#[test]
+fn shrinking_grid_fits_its_tracks() {
+    let mut grid = Node::new(Quad::new())
+        .grid([Length::Shrink; 2], [Length::Shrink])
+        .spacing(3)
+        .padding(2);
+    grid.push(fixed(10, 7));
+    grid.push(fixed(20, 5));
+    let mut root = Node::root_node(102, 52);
+    root.push(grid);
+    let layout = root.calculate_layout().children[0].layout;
+    assert_eq!((layout.w, layout.h), (37, 11));
+}
+
+#[test]
+fn placed_inside_the_cell() {
+    let mut grid = grid([Length::Fixed(20)], [Length::Fixed(20)])
+        .justify(Justify::Center)
+        .align(Align::End);
+    grid.push(fixed(10, 10));
+    let cell = cells(grid)[0];
+    assert_eq!((cell.x, cell.y), (6, 11));
+}
+
+#[test]
+fn tracks_that_dont_fit_overflow() {
+    let mut grid = grid([Length::Fixed(60); 2], [Length::Shrink]).name("inventory");
+    grid.push(fixed(10, 10));
+    let mut root = Node::root_node(102, 52);
+    root.push(grid);
+    assert!(matches!(
+        root.try_calculate_layout(),
+        Err(LayoutError::Overflow {
+            axis: Axis::Horizontal,
+            needed: 120,
+            ..
+        })
+    ));
+}
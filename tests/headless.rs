@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use icecube::button::Button;
+use icecube::headless::Headless;
+use icecube::layout::{Layout, Length};
+use icecube::palette::{BLUE_DARK, MAIN_LIGHT};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+use icecube::Input;
+
+#[derive(Debug, Clone, Copy)]
+enum Message {
+    Increment,
+    Tick(Duration),
+}
+
+#[derive(Default)]
+struct State {
+    count: u32,
+    elapsed: Duration,
+}
+
+fn update(m: Message, state: &mut State) {
+    match m {
+        Message::Increment => state.count += 1,
+        Message::Tick(d) => state.elapsed += d,
+    }
+}
+
+fn view<'a>(_state: &State) -> Node<'a, Message, Layout> {
+    let mut button = Node::new(Button::new().on_press(Message::Increment))
+        .width(Length::Fixed(20))
+        .height(Length::Fixed(10));
+    button.push(
+        Node::new(Quad::new().fill(BLUE_DARK))
+            .width(Length::Grow)
+            .height(Length::Grow),
+    );
+    button
+}
+
+fn click(x: u32, y: u32) -> Input {
+    Input {
+        mouse_released: true,
+        mouse_pos: Some((x, y)),
+        ..Input::default()
+    }
+}
+
+#[test]
+fn draws_without_a_window() {
+    let app = Headless::new(State::default(), update, view, 320, 240, MAIN_LIGHT, |_| {
+        None
+    });
+
+    assert_eq!(app.frame().len(), 320 * 240 * 4);
+    // the root node has 1px of padding
+    assert_eq!(app.pixel(0, 0), MAIN_LIGHT);
+    assert_eq!(app.pixel(1, 1), BLUE_DARK);
+    assert_eq!(app.pixel(20, 1), BLUE_DARK);
+    assert_eq!(app.pixel(21, 1), MAIN_LIGHT);
+    assert_eq!(app.pixel(1, 11), MAIN_LIGHT);
+}
+
+#[test]
+fn scripted_clicks_reach_update() {
+    let mut app = Headless::new(State::default(), update, view, 320, 240, MAIN_LIGHT, |_| {
+        None
+    });
+
+    app.run([
+        (click(5, 5), Duration::ZERO),
+        (Input::default(), Duration::ZERO),
+        (click(40, 20), Duration::ZERO),
+        (click(2, 2), Duration::ZERO),
+    ]);
+
+    assert_eq!(app.state().count, 2);
+}
+
+#[test]
+fn timer_sees_scripted_durations() {
+    let mut app = Headless::new(State::default(), update, view, 320, 240, MAIN_LIGHT, |d| {
+        Some(Message::Tick(d))
+    });
+
+    app.run((0..4).map(|_| (Input::default(), Duration::from_millis(250))));
+
+    assert_eq!(app.state().elapsed, Duration::from_secs(1));
+}
+
+#[test]
+fn frame_converts_to_an_image() {
+    let app = Headless::new(State::default(), update, view, 320, 240, MAIN_LIGHT, |_| {
+        None
+    });
+    let image = app.to_image();
+
+    assert_eq!(image.dimensions(), (320, 240));
+    assert_eq!(image.get_pixel(1, 1).0, BLUE_DARK);
+}
+
+#[test]
+fn draws_at_any_width() {
+    let view = |_: &State| -> Node<Message, Layout> {
+        let mut row = Node::new(Quad::new()).row().width(Length::Grow);
+        row.push(Node::spacer());
+        row.push(
+            Node::new(Quad::new().fill(BLUE_DARK))
+                .width(Length::Fixed(10))
+                .height(Length::Fixed(10)),
+        );
+        row
+    };
+    let app = Headless::new(State::default(), update, view, 100, 20, MAIN_LIGHT, |_| {
+        None
+    });
+
+    assert_eq!(app.pixel(88, 1), MAIN_LIGHT);
+    assert_eq!(app.pixel(89, 1), BLUE_DARK);
+    assert_eq!(app.pixel(98, 10), BLUE_DARK);
+    assert_eq!(app.pixel(99, 1), MAIN_LIGHT);
+    assert_eq!(app.pixel(0, 2), MAIN_LIGHT);
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use icecube::buffer::Buffer;
+use icecube::font::{Font, FontType, ImageFont, ImageFontMetrics};
+use icecube::headless::Headless;
+use icecube::layout::Layout;
+use icecube::palette::{MAIN_LIGHT, RED_DARK};
+use icecube::text::{break_lines, Text};
+use icecube::tree::Node;
+use icecube::Input;
+use image::GrayImage;
+
+/// 'A' to 'C' in 4x2 cells one pixel apart. 'A' lights the middle two columns of its top row,
+/// 'B' the bottom left and top right pixels, and 'C' is empty.
+fn font() -> ImageFont {
+    let mut image = GrayImage::new(14, 2);
+    for (x, y) in [(1, 0), (2, 0), (5, 1), (8, 0)] {
+        image.put_pixel(x, y, [255].into());
+    }
+    let metrics = ImageFontMetrics {
+        first_char: 'A',
+        last_char: 'C',
+        character_width: 4,
+        character_height: 2,
+        characters_per_row: 3,
+        x_spacing: 1,
+        y_spacing: 1,
+        x_margin: 0,
+        y_margin: 0,
+    };
+    ImageFont::new(image, metrics)
+}
+
+/// The pixels `font` lights drawing `character` at (2, 2)
+fn drawn(font: &ImageFont, character: char) -> Vec<(usize, usize)> {
+    let mut data = vec![0; 8 * 4 * 4];
+    font.draw_character(&mut Buffer::new(&mut data, 8, 4), 2, 2, character, RED_DARK);
+    data.chunks_exact(4)
+        .enumerate()
+        .filter(|(_, pixel)| *pixel == RED_DARK)
+        .map(|(i, _)| (i % 8, i / 8))
+        .collect()
+}
+
+#[test]
+fn monospace_by_default() {
+    let font = font();
+    assert_eq!(font.glyph_width('A'), 4);
+    assert_eq!(drawn(&font, 'A'), vec![(3, 2), (4, 2)]);
+}
+
+#[test]
+fn proportional_trims_empty_columns() {
+    let font = font().proportional();
+    assert_eq!(font.glyph_width('A'), 2);
+    assert_eq!(font.glyph_width('B'), 4);
+    // empty cells, and spaces, keep the cell width
+    assert_eq!(font.glyph_width('C'), 4);
+    assert_eq!(font.glyph_width(' '), 4);
+    // drawn from the first column with something in it
+    assert_eq!(drawn(&font, 'A'), vec![(2, 2), (3, 2)]);
+}
+
+#[test]
+fn widths_from_a_table() {
+    let font = font().glyph_widths([('A', 3), (' ', 2), ('Z', 1), ('B', 9)]);
+    assert_eq!(font.glyph_width('A'), 3);
+    assert_eq!(font.glyph_width(' '), 2);
+    // no wider than the cell
+    assert_eq!(font.glyph_width('B'), 4);
+    assert_eq!(drawn(&font, 'A'), vec![(3, 2), (4, 2)]);
+}
+
+#[test]
+fn kerning_tightens_wrapping() {
+    let kerned = font().proportional().kerning_pairs([(('A', 'B'), -1)]);
+    assert_eq!(kerned.kerning('A', 'B'), -1);
+    assert_eq!(kerned.kerning('B', 'A'), 0);
+    // each "AB" is 2 + 1 + 4 + 1 - 1 pixels, and a space is 4
+    let words = |font: &ImageFont| -> Vec<_> {
+        break_lines(font, "AB AB", 1, 0, 19)
+            .into_iter()
+            .map(|line| line.words)
+            .collect()
+    };
+    assert_eq!(words(&kerned), vec![vec![(0..2, 0), (3..5, 11)]]);
+    let unkerned = font().proportional();
+    assert_eq!(words(&unkerned), vec![vec![(0..2, 0)], vec![(3..5, 0)]]);
+}
+
+#[test]
+fn text_draws_kerned_pairs() {
+    let font: &'static FontType = Box::leak(Box::new(FontType::Image(
+        font().proportional().kerning_pairs([(('A', 'B'), -1)]),
+    )));
+    let view = move |_: &()| -> Node<'static, (), Layout> {
+        Node::new(Text::new("AB".into()).with_font(font).with_color(RED_DARK))
+    };
+    let mut app = Headless::new((), |_, _| {}, view, 16, 16, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    let lit: Vec<_> = (1..3)
+        .flat_map(|y| (1..9).map(move |x| (x, y)))
+        .filter(|(x, y)| app.pixel(*x, *y) == RED_DARK)
+        .collect();
+    // 'B' starts a pixel earlier than its advance alone would put it
+    assert_eq!(lit, vec![(1, 1), (2, 1), (6, 1), (3, 2)]);
+}
@@ -0,0 +1,59 @@
+use std::io::Cursor;
+
+use icecube::image::Image;
+use icecube::palette::{Color, BLUE_DARK, MAIN_DARK, MAIN_LIGHT, RED_DARK};
+use image::{ImageFormat, RgbaImage};
+
+const PIXELS: [Color; 4] = [RED_DARK, BLUE_DARK, MAIN_LIGHT, RED_DARK];
+
+/// A 2x2 image of `PIXELS`, encoded as `format`
+fn encoded(format: ImageFormat) -> Vec<u8> {
+    let image = RgbaImage::from_raw(2, 2, PIXELS.concat()).unwrap();
+    let mut bytes = Cursor::new(vec![]);
+    image.write_to(&mut bytes, format).unwrap();
+    bytes.into_inner()
+}
+
+#[test]
+fn decodes_png_gif_and_bmp() {
+    for format in [ImageFormat::Png, ImageFormat::Gif, ImageFormat::Bmp] {
+        let image = Image::from_bytes(&encoded(format)).unwrap();
+        assert_eq!((image.width, image.height), (2, 2), "{format:?}");
+        assert_eq!(image.data, PIXELS, "{format:?}");
+    }
+}
+
+#[test]
+fn opens_files() {
+    let path = std::env::temp_dir().join("icecube_image_loading.png");
+    std::fs::write(&path, encoded(ImageFormat::Png)).unwrap();
+    let image = Image::open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(image.data, PIXELS);
+}
+
+#[test]
+fn reports_bad_data() {
+    assert!(Image::from_bytes(b"not an image").is_err());
+    assert!(Image::open("does/not/exist.png").is_err());
+}
+
+#[test]
+fn quantize_snaps_to_the_palette() {
+    let image = Image::new(
+        vec![[0xfe, 0x6a, 0x70, 0xff], [0x10, 0x70, 0x90, 0x80], [0; 4]],
+        3,
+        1,
+    )
+    .quantize();
+    let with_alpha = |[r, g, b, _]: Color, a| [r, g, b, a];
+    // alpha is kept as it was
+    assert_eq!(
+        image.data,
+        vec![
+            RED_DARK,
+            with_alpha(BLUE_DARK, 0x80),
+            with_alpha(MAIN_DARK, 0)
+        ]
+    );
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::image::{Fit, Image};
+use icecube::layout::Layout;
+use icecube::palette::MAIN_LIGHT;
+use icecube::tree::Node;
+use icecube::Input;
+
+/// Red channel of the background, for pixels the image doesn't cover
+const BG: u8 = MAIN_LIGHT[0];
+
+/// 2x3 image numbered row by row in its red channel:
+/// ```text
+/// 0 1
+/// 2 3
+/// 4 5
+/// ```
+fn numbered() -> Image<[u8; 4]> {
+    Image::new((0..6).map(|i| [i, 0, 0, 255]).collect(), 2, 3)
+}
+
+/// Red channels of the `w` by `h` area at (1, 1), inside the root's padding, with `image` in a
+/// node of `size` (or shrinking when `None`)
+fn render(image: Image<[u8; 4]>, size: Option<(u32, u32)>, w: u32, h: u32) -> Vec<Vec<u8>> {
+    let view = move |_: &()| -> Node<'static, (), Layout> {
+        let node = Node::new(image.clone());
+        match size {
+            Some((w, h)) => node.width(w).height(h),
+            None => node,
+        }
+    };
+    let mut app = Headless::new((), |_, _| {}, view, 16, 16, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    (1..=h)
+        .map(|y| (1..=w).map(|x| app.pixel(x, y)[0]).collect())
+        .collect()
+}
+
+fn shown(image: Image<[u8; 4]>, w: u32, h: u32) -> Vec<Vec<u8>> {
+    render(image, None, w, h)
+}
+
+#[test]
+fn flips() {
+    assert_eq!(
+        shown(numbered().flip_horizontal(), 2, 3),
+        vec![vec![1, 0], vec![3, 2], vec![5, 4]]
+    );
+    assert_eq!(
+        shown(numbered().flip_vertical(), 2, 3),
+        vec![vec![4, 5], vec![2, 3], vec![0, 1]]
+    );
+}
+
+#[test]
+fn rotations() {
+    assert_eq!(
+        shown(numbered().rotate(1), 3, 2),
+        vec![vec![4, 2, 0], vec![5, 3, 1]]
+    );
+    assert_eq!(
+        shown(numbered().rotate(2), 2, 3),
+        vec![vec![5, 4], vec![3, 2], vec![1, 0]]
+    );
+    assert_eq!(
+        shown(numbered().rotate(3), 3, 2),
+        vec![vec![1, 3, 5], vec![0, 2, 4]]
+    );
+    // four turns are back where they started
+    assert_eq!(
+        shown(numbered().rotate(3).rotate(1), 2, 3),
+        shown(numbered(), 2, 3)
+    );
+    assert_eq!(
+        shown(numbered().rotate(3).rotate(u8::MAX), 2, 3),
+        shown(numbered().rotate(2), 2, 3)
+    );
+}
+
+#[test]
+fn flip_happens_before_rotation() {
+    assert_eq!(
+        shown(numbered().flip_horizontal().rotate(1), 3, 2),
+        vec![vec![5, 3, 1], vec![4, 2, 0]]
+    );
+}
+
+#[test]
+fn crop() {
+    assert_eq!(
+        shown(numbered().crop(1, 1, 1, 2).scale_factor(2), 3, 4),
+        vec![
+            vec![3, 3, BG],
+            vec![3, 3, BG],
+            vec![5, 5, BG],
+            vec![5, 5, BG]
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "crop goes past the edge of the image")]
+fn crop_past_the_edge() {
+    let _ = numbered().crop(1, 0, u32::MAX, 1);
+}
+
+#[test]
+fn center() {
+    let frame = render(numbered().fit(Fit::Center), Some((6, 7)), 6, 7);
+    assert_eq!(frame[2], vec![BG, BG, 0, 1, BG, BG]);
+    assert_eq!(frame[4], vec![BG, BG, 4, 5, BG, BG]);
+    assert_eq!(frame[5], vec![BG; 6]);
+}
+
+#[test]
+fn integer_fit_picks_the_largest_whole_scale() {
+    let frame = render(numbered().fit(Fit::IntegerFit), Some((10, 10)), 10, 10);
+    // scaled 3 times, to 6x9, and centered
+    assert_eq!(frame[0], vec![BG, BG, 0, 0, 0, 1, 1, 1, BG, BG]);
+    assert_eq!(frame[8][2], 4);
+    assert_eq!(frame[9][2], BG);
+}
+
+#[test]
+fn contain_keeps_the_aspect_ratio() {
+    let frame = render(numbered().fit(Fit::Contain), Some((12, 6)), 12, 6);
+    // 4x6, centered across
+    assert_eq!(frame[0], vec![BG, BG, BG, BG, 0, 0, 1, 1, BG, BG, BG, BG]);
+    assert_eq!(frame[5][4], 4);
+}
+
+#[test]
+fn minimum_size_follows_the_fit() {
+    let size = |image: Image<[u8; 4]>| {
+        let mut root: Node<(), Layout> = Node::root_node(32, 32);
+        root.push(Node::new(image));
+        let layout = root.calculate_layout().children[0].layout;
+        (layout.w, layout.h)
+    };
+    assert_eq!(size(numbered().scale_factor(2)), (4, 6));
+    assert_eq!(size(numbered().scale_factor(2).rotate(1)), (6, 4));
+    assert_eq!(
+        size(numbered().scale_factor(2).fit(Fit::IntegerFit)),
+        (2, 3)
+    );
+    assert_eq!(size(numbered().fit(Fit::Contain)), (0, 0));
+}
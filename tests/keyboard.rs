@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::key_binding::{KeyBinding, Shortcut};
+use icecube::layout::{Layout, Length};
+use icecube::palette::MAIN_LIGHT;
+use icecube::tree::Node;
+use icecube::{Input, KeyCode, Modifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Message {
+    Pause,
+    Undo,
+    Redo,
+}
+
+fn update(m: Message, log: &mut Vec<Message>) {
+    log.push(m);
+}
+
+fn view<'a>(_log: &Vec<Message>) -> Node<'a, Message, Layout> {
+    Node::new(
+        KeyBinding::new()
+            .on_key(KeyCode::Space, Message::Pause)
+            .on_key(Shortcut::control(KeyCode::KeyZ), Message::Undo)
+            .on_key(
+                Shortcut::new(
+                    Modifiers {
+                        shift: true,
+                        control: true,
+                        alt: false,
+                    },
+                    KeyCode::KeyZ,
+                ),
+                Message::Redo,
+            ),
+    )
+    .width(Length::Grow)
+    .height(Length::Grow)
+}
+
+fn press(key: KeyCode, modifiers: Modifiers) -> (Input, Duration) {
+    let input = Input {
+        keys_pressed: vec![key],
+        keys_held: vec![key],
+        modifiers,
+        ..Input::default()
+    };
+    (input, Duration::ZERO)
+}
+
+#[test]
+fn key_presses_become_messages() {
+    let mut app = Headless::new(vec![], update, view, 32, 32, MAIN_LIGHT, |_| None);
+
+    app.run([
+        press(KeyCode::Space, Modifiers::NONE),
+        press(KeyCode::KeyZ, Modifiers::CONTROL),
+        press(KeyCode::KeyZ, Modifiers::NONE),
+        press(KeyCode::KeyZ, Modifiers::SHIFT),
+        press(
+            KeyCode::KeyZ,
+            Modifiers {
+                shift: true,
+                control: true,
+                alt: false,
+            },
+        ),
+    ]);
+
+    assert_eq!(
+        app.state(),
+        &vec![Message::Pause, Message::Undo, Message::Redo]
+    );
+}
+
+#[test]
+fn held_and_repeated_keys_dont_retrigger() {
+    let mut app = Headless::new(vec![], update, view, 32, 32, MAIN_LIGHT, |_| None);
+
+    let held = Input {
+        keys_held: vec![KeyCode::Space],
+        ..Input::default()
+    };
+    let repeated = Input {
+        keys_repeated: vec![KeyCode::Space],
+        keys_held: vec![KeyCode::Space],
+        ..Input::default()
+    };
+    app.run([
+        press(KeyCode::Space, Modifiers::NONE),
+        (held, Duration::ZERO),
+        (repeated, Duration::ZERO),
+    ]);
+
+    assert_eq!(app.state(), &vec![Message::Pause]);
+}
+
+#[test]
+fn input_key_queries() {
+    let input = Input {
+        keys_pressed: vec![KeyCode::KeyA],
+        keys_repeated: vec![KeyCode::Backspace],
+        keys_released: vec![KeyCode::KeyB],
+        keys_held: vec![KeyCode::KeyA, KeyCode::Backspace],
+        ..Input::default()
+    };
+
+    assert!(input.key_pressed(KeyCode::KeyA));
+    assert!(!input.key_pressed(KeyCode::Backspace));
+    assert!(input.key_pressed_or_repeated(KeyCode::Backspace));
+    assert!(input.key_released(KeyCode::KeyB));
+    assert!(input.key_held(KeyCode::Backspace));
+    assert!(!input.key_held(KeyCode::KeyB));
+}
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::layout::{Axis, Layout, LayoutError, Length};
+use icecube::palette::MAIN_LIGHT;
+use icecube::quad::Quad;
+use icecube::text::Text;
+use icecube::tree::Node;
+use icecube::Input;
+
+fn fixed(w: u32, h: u32) -> Node<'static, (), Layout> {
+    Node::new(Quad::new()).width(w).height(h)
+}
+
+#[test]
+fn layout_that_fits() {
+    let mut root = Node::root_node(102, 52);
+    root.push(fixed(100, 50));
+    assert!(root.try_calculate_layout().is_ok());
+}
+
+#[test]
+fn root_not_fixed() {
+    let mut root = Node::new(Quad::new()).padding(1);
+    root.push(fixed(20, 10));
+    assert_eq!(
+        root.try_calculate_layout().unwrap_err(),
+        LayoutError::RootNotFixed
+    );
+
+    // without checking, the root shrinks to its content
+    let mut root = Node::new(Quad::new()).padding(1);
+    root.push(fixed(20, 10));
+    let layout = root.calculate_layout().layout;
+    assert_eq!((layout.w, layout.h), (22, 12));
+}
+
+#[test]
+fn overflow_names_the_node() {
+    let mut root = Node::root_node(102, 52);
+    let mut toolbar = Node::new(Quad::new())
+        .name("toolbar")
+        .width(Length::Grow)
+        .spacing(2);
+    toolbar.push(fixed(60, 10));
+    toolbar.push(fixed(60, 10));
+    root.push(toolbar);
+
+    let error = root.try_calculate_layout().unwrap_err();
+    assert_eq!(
+        error,
+        LayoutError::Overflow {
+            node: Some("toolbar".to_string()),
+            axis: Axis::Horizontal,
+            needed: 122,
+            available: 100,
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "children of `toolbar` need 122px of width but only 100px is available"
+    );
+}
+
+#[test]
+fn vertical_overflow() {
+    let mut root = Node::root_node(102, 52).column();
+    root.push(fixed(10, 30));
+    root.push(fixed(10, 30));
+    assert!(matches!(
+        root.try_calculate_layout(),
+        Err(LayoutError::Overflow {
+            axis: Axis::Vertical,
+            needed: 60,
+            available: 50,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn growing_child_wider_than_parent_falls_back_to_its_minimum() {
+    let mut root: Node<(), Layout> = Node::root_node(102, 52).column();
+    root.push(
+        Node::new(Quad::new())
+            .width(Length::Grow)
+            .min_width(150)
+            .height(10),
+    );
+    assert!(root.try_calculate_layout().is_err());
+
+    let mut root: Node<(), Layout> = Node::root_node(102, 52).column();
+    root.push(
+        Node::new(Quad::new())
+            .width(Length::Grow)
+            .min_width(150)
+            .height(10),
+    );
+    assert_eq!(root.calculate_layout().children[0].layout.w, 150);
+}
+
+#[test]
+fn long_word_in_narrow_node_is_clipped() {
+    let view = |_: &()| -> Node<'static, (), Layout> {
+        let mut column = Node::new(Quad::new()).width(10).height(Length::Grow);
+        column.push(Node::new(Text::new("Donaudampfschifffahrt ok".to_string())));
+        column
+    };
+    let mut app = Headless::new((), |_, _| {}, view, 64, 32, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    // nothing is drawn past the column
+    for x in 11..64 {
+        for y in 0..32 {
+            assert_eq!(app.pixel(x, y), MAIN_LIGHT);
+        }
+    }
+}
+
+#[test]
+fn headless_reports_layout_errors() {
+    // the timer widens the toolbar past the screen on the first step
+    let view = |wide: &bool| -> Node<'static, bool, Layout> {
+        let mut toolbar = Node::new(Quad::new()).name("toolbar").width(Length::Grow);
+        for _ in 0..(1 + *wide as usize) {
+            toolbar.push(Node::new(Quad::new()).width(40).height(10));
+        }
+        toolbar
+    };
+    let update = |wide, state: &mut bool| *state = wide;
+    let mut app = Headless::new(false, update, view, 64, 32, MAIN_LIGHT, |_| Some(true));
+    assert!(app.layout_errors().is_empty());
+
+    app.step(&Input::default(), Duration::ZERO);
+    assert_eq!(
+        app.layout_errors(),
+        [LayoutError::Overflow {
+            node: Some("toolbar".to_string()),
+            axis: Axis::Horizontal,
+            needed: 80,
+            available: 62,
+        }]
+    );
+}
@@ -0,0 +1,88 @@
+use icecube::layout::{CalculatedLayout, Layout, Length};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+
+fn quad(width: Length, height: Length) -> Node<'static, (), Layout> {
+    Node::new(Quad::new()).width(width).height(height)
+}
+
+fn positions(root: Node<'static, (), Layout>) -> Vec<CalculatedLayout> {
+    root.calculate_layout()
+        .children
+        .into_iter()
+        .map(|c| c.layout)
+        .collect()
+}
+
+/// Root whose inner area is `w`x`h` starting at (1, 1)
+fn root(w: usize, h: usize) -> Node<'static, (), Layout> {
+    Node::root_node(w + 2, h + 2)
+}
+
+#[test]
+fn fill_portion_row() {
+    let mut root = root(100, 50).row();
+    root.push(quad(Length::FillPortion(1), Length::Grow));
+    root.push(quad(Length::FillPortion(2), Length::Grow));
+    root.push(quad(Length::FillPortion(1), Length::Grow));
+    let layouts = positions(root);
+    assert_eq!(
+        layouts.iter().map(|l| l.w).collect::<Vec<_>>(),
+        vec![25, 50, 25]
+    );
+    assert_eq!(
+        layouts.iter().map(|l| l.x).collect::<Vec<_>>(),
+        vec![1, 26, 76]
+    );
+}
+
+#[test]
+fn grow_is_fill_portion_one() {
+    let mut root = root(90, 50).column();
+    root.push(quad(Length::Grow, Length::Grow));
+    root.push(quad(Length::Grow, Length::FillPortion(2)));
+    let heights: Vec<_> = positions(root).iter().map(|l| l.h).collect();
+    assert_eq!(heights, vec![16, 34]);
+}
+
+#[test]
+fn remainder_is_not_lost() {
+    let mut root = root(100, 50).row().spacing(1);
+    for _ in 0..3 {
+        root.push(quad(Length::Grow, Length::Grow));
+    }
+    let layouts = positions(root);
+    // 98px between three children doesn't divide evenly, but every pixel is used
+    assert_eq!(layouts.iter().map(|l| l.w).sum::<u32>(), 98);
+    let last = layouts.last().unwrap();
+    assert_eq!(last.x + last.w, 101);
+}
+
+#[test]
+fn percent() {
+    let mut root = root(200, 50).row();
+    root.push(quad(Length::Percent(25), Length::Percent(50)));
+    root.push(quad(Length::Grow, Length::Grow));
+    root.push(quad(Length::Fixed(10), Length::Fixed(10)));
+    let layouts = positions(root);
+    assert_eq!(
+        layouts.iter().map(|l| l.w).collect::<Vec<_>>(),
+        vec![50, 140, 10]
+    );
+    assert_eq!(layouts[0].h, 25);
+}
+
+#[test]
+fn percent_of_padded_parent() {
+    let mut root = root(100, 50);
+    let mut column = Node::new(Quad::new())
+        .width(Length::Grow)
+        .height(Length::Grow)
+        .padding(5)
+        .column();
+    column.push(quad(Length::Percent(50), Length::Percent(100)));
+    root.push(column);
+    let column = root.calculate_layout().children.remove(0);
+    let child = column.children[0].layout;
+    assert_eq!((child.x, child.y, child.w, child.h), (6, 6, 45, 40));
+}
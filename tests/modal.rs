@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::layout::Length;
+use icecube::modal::{Layers, Modal};
+use icecube::mouse_area::MouseArea;
+use icecube::palette::{BLUE_DARK, MAIN_DARK, MAIN_LIGHT, RED_DARK};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+use icecube::{Input, KeyCode};
+
+#[derive(Default)]
+struct State {
+    confirming: bool,
+    deleted: bool,
+    background_clicks: u32,
+}
+
+enum Message {
+    Clicked,
+    Confirm,
+    Cancel,
+}
+
+fn update(message: Message, state: &mut State) {
+    match message {
+        Message::Clicked => state.background_clicks += 1,
+        Message::Confirm => {
+            state.deleted = true;
+            state.confirming = false;
+        }
+        Message::Cancel => state.confirming = false,
+    }
+}
+
+/// A screen-filling button under a 20x10 dialog, centered in the 64x32 frame at (22, 11)
+fn view<'a>(state: &State) -> Layers<'a, Message> {
+    let mut background = Node::new(MouseArea::new().on_press(|_| Message::Clicked))
+        .width(Length::Grow)
+        .height(Length::Grow);
+    background.push(
+        Node::new(Quad::new().fill(BLUE_DARK))
+            .width(Length::Grow)
+            .height(Length::Grow),
+    );
+    let layers = Layers::new(background);
+    if !state.confirming {
+        return layers;
+    }
+
+    let mut yes = Node::new(MouseArea::new().on_press(|_| Message::Confirm))
+        .width(20)
+        .height(10);
+    yes.push(
+        Node::new(Quad::new().fill(RED_DARK))
+            .width(Length::Grow)
+            .height(Length::Grow),
+    );
+    layers.modal(Modal::new(yes).on_dismiss(|| Message::Cancel))
+}
+
+type App = Headless<
+    'static,
+    State,
+    Message,
+    fn(Message, &mut State),
+    fn(&State) -> Layers<'static, Message>,
+    fn(Duration) -> Option<Message>,
+>;
+
+fn app(confirming: bool) -> App {
+    Headless::new(
+        State {
+            confirming,
+            ..State::default()
+        },
+        update,
+        view,
+        64,
+        32,
+        MAIN_LIGHT,
+        |_| None,
+    )
+}
+
+fn click(x: u32, y: u32) -> (Input, Duration) {
+    let input = Input {
+        mouse_released: true,
+        mouse_pos: Some((x, y)),
+        ..Input::default()
+    };
+    (input, Duration::ZERO)
+}
+
+#[test]
+fn background_is_dimmed() {
+    let app = app(true);
+    assert_eq!(app.pixel(4, 4), MAIN_DARK);
+    assert_eq!(app.pixel(5, 4), BLUE_DARK);
+    assert_eq!(app.pixel(30, 15), RED_DARK);
+}
+
+#[test]
+fn clicks_outside_dismiss_without_reaching_the_main_view() {
+    let mut app = app(true);
+    app.run([click(5, 5)]);
+    assert!(!app.state().confirming);
+    assert_eq!(app.state().background_clicks, 0);
+
+    // closed, so the main view gets clicks again
+    app.run([click(5, 5)]);
+    assert_eq!(app.state().background_clicks, 1);
+    assert_eq!(app.pixel(4, 4), BLUE_DARK);
+}
+
+#[test]
+fn dialog_gets_clicks() {
+    let mut app = app(true);
+    app.run([click(30, 15)]);
+    assert!(app.state().deleted);
+    assert!(!app.state().confirming);
+    assert_eq!(app.state().background_clicks, 0);
+}
+
+#[test]
+fn escape_dismisses() {
+    let mut app = app(true);
+    let escape = Input {
+        keys_pressed: vec![KeyCode::Escape],
+        ..Input::default()
+    };
+    app.run([(escape, Duration::ZERO)]);
+    assert!(!app.state().confirming);
+    assert!(!app.state().deleted);
+}
+
+#[test]
+fn views_without_a_modal_are_untouched() {
+    let mut app = app(false);
+    app.run([click(30, 15)]);
+    assert_eq!(app.state().background_clicks, 1);
+    assert_eq!(app.pixel(4, 4), BLUE_DARK);
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::image::Image;
+use icecube::layout::{Layout, Length};
+use icecube::nine_slice::{NineSlice, SliceFill};
+use icecube::palette::{Color, MAIN_LIGHT};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+use icecube::Input;
+
+/// Pixel `i` of a test image, told apart by its red channel
+fn px(i: u8) -> Color {
+    [i, 0, 0, 255]
+}
+
+/// A `width` by `height` image whose pixels are numbered row by row
+fn numbered(width: usize, height: usize) -> Image<Color> {
+    Image::new((0..(width * height) as u8).map(px).collect(), width, height)
+}
+
+/// Red channels of the `w` by `h` area at (1, 1), inside the root's padding
+fn render(
+    view: impl Fn(&()) -> Node<'static, (), Layout> + 'static,
+    w: u32,
+    h: u32,
+) -> Vec<Vec<u8>> {
+    let mut app = Headless::new((), |_, _| {}, view, 16, 16, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    (1..=h)
+        .map(|y| (1..=w).map(|x| app.pixel(x, y)[0]).collect())
+        .collect()
+}
+
+#[test]
+fn corners_stay_and_edges_fill() {
+    let frame = render(
+        |_| {
+            Node::from(NineSlice::new(numbered(3, 3), 1))
+                .width(5)
+                .height(4)
+        },
+        5,
+        4,
+    );
+    assert_eq!(
+        frame,
+        vec![
+            vec![0, 1, 1, 1, 2],
+            vec![3, 4, 4, 4, 5],
+            vec![3, 4, 4, 4, 5],
+            vec![6, 7, 7, 7, 8],
+        ]
+    );
+}
+
+#[test]
+fn tile_or_stretch() {
+    let slice = || NineSlice::new(numbered(4, 3), 1);
+    let tiled = render(move |_| Node::from(slice()).width(6).height(3), 6, 1);
+    assert_eq!(tiled, vec![vec![0, 1, 2, 1, 2, 3]]);
+    let stretched = render(
+        move |_| {
+            Node::from(slice().fill(SliceFill::Stretch))
+                .width(6)
+                .height(3)
+        },
+        6,
+        1,
+    );
+    assert_eq!(stretched, vec![vec![0, 1, 1, 2, 2, 3]]);
+}
+
+#[test]
+fn scale_factor_scales_the_corners() {
+    let frame = render(
+        |_| {
+            Node::from(NineSlice::new(numbered(3, 3).scale_factor(2), 1))
+                .width(6)
+                .height(4)
+        },
+        6,
+        2,
+    );
+    assert_eq!(frame, vec![vec![0, 0, 1, 1, 2, 2]; 2]);
+}
+
+#[test]
+fn shrinks_to_its_corners_and_pads_children() {
+    let mut root: Node<(), Layout> = Node::root_node(32, 32);
+    let mut frame = Node::from(NineSlice::new(numbered(3, 3).scale_factor(2), [1, 1, 1, 1]));
+    frame.push(
+        Node::new(Quad::new())
+            .width(Length::Fixed(4))
+            .height(Length::Fixed(3)),
+    );
+    root.push(frame);
+    let frame = root.calculate_layout().children.remove(0);
+    assert_eq!((frame.layout.w, frame.layout.h), (8, 7));
+    let child = frame.children[0].layout;
+    assert_eq!((child.x, child.y), (3, 3));
+}
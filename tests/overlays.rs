@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::layout::{Anchor, CalculatedLayout, Layout, Length};
+use icecube::mouse_area::MouseArea;
+use icecube::palette::{BLUE_DARK, MAIN_LIGHT, RED_DARK};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+use icecube::Input;
+
+fn fixed(w: u32, h: u32) -> Node<'static, (), Layout> {
+    Node::new(Quad::new()).width(w).height(h)
+}
+
+/// Lays `stack` out in a root whose inner area is 100x50 starting at (1, 1), returning where
+/// it and its children ended up
+fn layout(stack: Node<'static, (), Layout>) -> (CalculatedLayout, Vec<CalculatedLayout>) {
+    let mut root = Node::root_node(102, 52);
+    root.push(stack);
+    let stack = root.calculate_layout().children.remove(0);
+    (
+        stack.layout,
+        stack.children.into_iter().map(|c| c.layout).collect(),
+    )
+}
+
+#[test]
+fn anchored_children() {
+    let mut stack = Node::new(Quad::new())
+        .stack()
+        .width(Length::Grow)
+        .height(Length::Grow);
+    stack.push(fixed(10, 10).anchor(Anchor::TopRight, (-2, 2)));
+    stack.push(fixed(20, 10).anchor(Anchor::Center, (0, 0)));
+    stack.push(fixed(10, 10).anchor(Anchor::BottomLeft, (0, 0)));
+    stack.push(fixed(10, 10).at(5, 7));
+    let (_, children) = layout(stack);
+    assert_eq!(
+        children.iter().map(|c| (c.x, c.y)).collect::<Vec<_>>(),
+        vec![(89, 3), (41, 21), (1, 41), (6, 8)]
+    );
+}
+
+#[test]
+fn offsets_stop_at_the_screen_edge() {
+    let mut stack = Node::new(Quad::new()).stack().width(50).height(50);
+    stack.push(fixed(10, 10).anchor(Anchor::TopLeft, (-5, -20)));
+    let (_, children) = layout(stack);
+    assert_eq!((children[0].x, children[0].y), (0, 0));
+}
+
+#[test]
+fn floating_children_dont_grow_a_stack() {
+    let mut stack = Node::new(Quad::new()).stack();
+    stack.push(fixed(20, 20));
+    stack.push(fixed(40, 40).anchor(Anchor::Center, (0, 0)));
+    let (stack, children) = layout(stack);
+    assert_eq!((stack.w, stack.h), (20, 20));
+    // centred over the stack, as far as the screen edge allows
+    assert_eq!((children[1].x, children[1].y), (0, 0));
+}
+
+fn overlapping<'a>(front_z: i32) -> Node<'a, usize, Layout> {
+    let mut stack = Node::new(Quad::new())
+        .stack()
+        .width(Length::Grow)
+        .height(Length::Grow);
+    for (i, (color, z_index)) in [(RED_DARK, front_z), (BLUE_DARK, 0)]
+        .into_iter()
+        .enumerate()
+    {
+        let mut area = Node::new(MouseArea::new().on_press(move |_| i))
+            .width(20)
+            .height(20)
+            .at(i as u32 * 10, 0)
+            .z_index(z_index);
+        area.push(
+            Node::new(Quad::new().fill(color))
+                .width(Length::Grow)
+                .height(Length::Grow),
+        );
+        stack.push(area);
+    }
+    stack
+}
+
+/// Clicks where the two squares of [`overlapping`] overlap, returning which one got the click
+/// and the color drawn there
+fn click_overlap(front_z: i32) -> (Option<usize>, [u8; 4]) {
+    let mut app = Headless::new(
+        None,
+        |i, clicked: &mut Option<usize>| *clicked = Some(i),
+        move |_: &Option<usize>| overlapping(front_z),
+        64,
+        32,
+        MAIN_LIGHT,
+        |_| None,
+    );
+    let click = Input {
+        mouse_released: true,
+        mouse_pos: Some((15, 5)),
+        ..Input::default()
+    };
+    app.run([(click, Duration::ZERO)]);
+    (*app.state(), app.to_image().get_pixel(15, 5).0)
+}
+
+#[test]
+fn later_siblings_are_in_front() {
+    assert_eq!(click_overlap(0), (Some(1), BLUE_DARK));
+}
+
+#[test]
+fn z_index_brings_a_sibling_forward() {
+    assert_eq!(click_overlap(1), (Some(0), RED_DARK));
+}
+
+#[test]
+fn overlays_without_input_still_cover() {
+    let view = |_: &Option<usize>| {
+        let mut stack = Node::new(Quad::new())
+            .stack()
+            .width(Length::Grow)
+            .height(Length::Grow);
+        stack.push(
+            Node::new(MouseArea::new().on_press(|_| 0))
+                .width(20)
+                .height(20),
+        );
+        stack.push(Node::new(Quad::new().fill(RED_DARK)).width(10).height(10));
+        stack
+    };
+    let mut app = Headless::new(
+        None,
+        |i, clicked: &mut Option<usize>| *clicked = Some(i),
+        view,
+        64,
+        32,
+        MAIN_LIGHT,
+        |_| None,
+    );
+    let click = |x, y| Input {
+        mouse_released: true,
+        mouse_pos: Some((x, y)),
+        ..Input::default()
+    };
+
+    app.run([(click(5, 5), Duration::ZERO)]);
+    assert_eq!(*app.state(), None);
+    app.run([(click(15, 15), Duration::ZERO)]);
+    assert_eq!(*app.state(), Some(0));
+}
+
+#[test]
+fn empty_layers_dont_cover() {
+    let view = |_: &Option<usize>| {
+        let mut stack = Node::new(Quad::new())
+            .stack()
+            .width(Length::Grow)
+            .height(Length::Grow);
+        stack.push(
+            Node::new(MouseArea::new().on_press(|_| 0))
+                .width(20)
+                .height(20),
+        );
+        // a full-size layer that only lays out a badge in its corner
+        let mut layer = Node::new(Quad::new())
+            .column()
+            .width(Length::Grow)
+            .height(Length::Grow);
+        layer.push(Node::new(Quad::new().fill(RED_DARK)).width(4).height(4));
+        stack.push(layer);
+        stack
+    };
+    let mut app = Headless::new(
+        None,
+        |i, clicked: &mut Option<usize>| *clicked = Some(i),
+        view,
+        64,
+        32,
+        MAIN_LIGHT,
+        |_| None,
+    );
+    let click = |x, y| Input {
+        mouse_released: true,
+        mouse_pos: Some((x, y)),
+        ..Input::default()
+    };
+
+    // the badge covers the area behind it, the rest of the layer doesn't
+    app.run([(click(2, 2), Duration::ZERO)]);
+    assert_eq!(*app.state(), None);
+    app.run([(click(15, 15), Duration::ZERO)]);
+    assert_eq!(*app.state(), Some(0));
+}
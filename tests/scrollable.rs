@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::layout::{Layout, Length};
+use icecube::mouse_area::MouseArea;
+use icecube::palette::{BLUE_DARK, BLUE_LIGHT, MAIN_DARK, MAIN_LIGHT, RED_DARK, RED_LIGHT};
+use icecube::quad::Quad;
+use icecube::scrollable::Scrollable;
+use icecube::tree::Node;
+use icecube::Input;
+use image::RgbaImage;
+
+const COLORS: [[u8; 4]; 5] = [RED_DARK, BLUE_DARK, RED_LIGHT, BLUE_LIGHT, MAIN_DARK];
+
+/// A 30px tall scrollable holding five 10px tall rows, clicking one records its index
+fn view<'a>(_clicked: &Option<usize>) -> Node<'a, usize, Layout> {
+    let list: Node<usize, Layout> = Scrollable::new().into();
+    let mut list = list.width(Length::Grow);
+    for (i, color) in COLORS.into_iter().enumerate() {
+        let mut row = Node::new(MouseArea::new().on_press(move |_| i))
+            .width(Length::Grow)
+            .height(10);
+        row.push(
+            Node::new(Quad::new().fill(color))
+                .width(Length::Grow)
+                .height(Length::Grow),
+        );
+        list.push(row);
+    }
+    list
+}
+
+fn run(script: &[Input]) -> (Option<usize>, RgbaImage) {
+    let mut app = Headless::new(
+        None,
+        |i, clicked: &mut Option<usize>| *clicked = Some(i),
+        view,
+        64,
+        32,
+        MAIN_LIGHT,
+        |_| None,
+    );
+    app.run(script.iter().map(|input| (input.clone(), Duration::ZERO)));
+    (*app.state(), app.to_image())
+}
+
+fn wheel(y: i32) -> Input {
+    Input {
+        mouse_pos: Some((10, 10)),
+        wheel_delta: (0, y),
+        ..Input::default()
+    }
+}
+
+fn click(x: u32, y: u32) -> Input {
+    Input {
+        mouse_released: true,
+        mouse_pos: Some((x, y)),
+        ..Input::default()
+    }
+}
+
+#[test]
+fn children_keep_their_height() {
+    let (_, frame) = run(&[]);
+    // the root node has 1px of padding
+    assert_eq!(frame.get_pixel(5, 5).0, RED_DARK);
+    assert_eq!(frame.get_pixel(5, 25).0, RED_LIGHT);
+    // the thumb covers the top 30 / 50 of the track
+    assert_eq!(frame.get_pixel(61, 5).0, BLUE_DARK);
+    assert_eq!(frame.get_pixel(61, 25).0, BLUE_LIGHT);
+}
+
+#[test]
+fn wheel_scrolls() {
+    let (_, frame) = run(&[wheel(-10)]);
+    assert_eq!(frame.get_pixel(5, 5).0, BLUE_DARK);
+    assert_eq!(frame.get_pixel(5, 25).0, BLUE_LIGHT);
+}
+
+#[test]
+fn scrolling_stops_at_the_end() {
+    let (_, frame) = run(&[wheel(-1000)]);
+    assert_eq!(frame.get_pixel(5, 1).0, RED_LIGHT);
+    assert_eq!(frame.get_pixel(5, 30).0, MAIN_DARK);
+    assert_eq!(frame.get_pixel(61, 30).0, BLUE_DARK);
+
+    let (_, frame) = run(&[wheel(-1000), wheel(5)]);
+    assert_eq!(frame.get_pixel(5, 25).0, BLUE_LIGHT);
+}
+
+#[test]
+fn wheel_outside_does_nothing() {
+    let outside = Input {
+        mouse_pos: None,
+        wheel_delta: (0, -10),
+        ..Input::default()
+    };
+    let (_, frame) = run(&[outside]);
+    assert_eq!(frame.get_pixel(5, 5).0, RED_DARK);
+}
+
+#[test]
+fn dragging_the_scrollbar() {
+    let press = |y| Input {
+        mouse_down: true,
+        mouse_pos: Some((61, y)),
+        ..Input::default()
+    };
+    // pressing the track below the thumb pulls the thumb down to the end
+    let (_, frame) = run(&[press(30)]);
+    assert_eq!(frame.get_pixel(5, 30).0, MAIN_DARK);
+
+    // and dragging it back up scrolls back to the start
+    let (_, frame) = run(&[press(30), press(10), press(1)]);
+    assert_eq!(frame.get_pixel(5, 5).0, RED_DARK);
+}
+
+#[test]
+fn clicks_reach_scrolled_children() {
+    assert_eq!(run(&[click(10, 5)]).0, Some(0));
+    assert_eq!(run(&[wheel(-10), click(10, 5)]).0, Some(1));
+    assert_eq!(run(&[wheel(-1000), click(10, 30)]).0, Some(4));
+}
+
+#[test]
+fn clipped_children_get_no_clicks_before_scrolling() {
+    // the fourth row starts at y = 31, just below the scrollable
+    assert_eq!(run(&[click(10, 31)]).0, None);
+}
+
+/// A 20px tall scrollable holding the five rows, inside one that also holds a 40px block below
+fn nested_view<'a>(clicked: &Option<usize>) -> Node<'a, usize, Layout> {
+    let outer: Node<usize, Layout> = Scrollable::new().into();
+    let mut outer = outer.width(Length::Grow);
+    outer.push(view(clicked).height(20));
+    outer.push(
+        Node::new(Quad::new().fill(MAIN_DARK))
+            .width(Length::Grow)
+            .height(40),
+    );
+    outer
+}
+
+#[test]
+fn innermost_scrollable_takes_the_wheel() {
+    let mut app = Headless::new(None, |_, _| {}, nested_view, 64, 32, MAIN_LIGHT, |_| None);
+    let wheel_at = |y| Input {
+        mouse_pos: Some((10, y)),
+        wheel_delta: (0, -10),
+        ..Input::default()
+    };
+
+    // only the inner one moves, so its third row is still there below its second
+    app.step(&wheel_at(10), Duration::ZERO);
+    assert_eq!(app.pixel(5, 5), BLUE_DARK);
+    assert_eq!(app.pixel(5, 15), RED_LIGHT);
+
+    // outside the inner one, the outer one scrolls
+    app.step(&wheel_at(25), Duration::ZERO);
+    assert_eq!(app.pixel(5, 15), MAIN_DARK);
+}
+
+#[test]
+fn scrolling_content_doesnt_overflow() {
+    let mut root = Node::root_node(64, 32);
+    root.push(view(&None));
+    assert!(root.try_calculate_layout().is_ok());
+}
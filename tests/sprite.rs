@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::image::Image;
+use icecube::layout::Layout;
+use icecube::palette::{Color, BLUE_DARK, BLUE_LIGHT, MAIN_LIGHT, RED_DARK};
+use icecube::sprite::{AnimatedSprite, Playback, SpriteSheet};
+use icecube::tree::Node;
+use icecube::Input;
+
+const FRAMES: [Color; 3] = [RED_DARK, BLUE_DARK, BLUE_LIGHT];
+
+/// Three 1x1 frames side by side, drawn 4 times bigger
+fn sheet() -> SpriteSheet {
+    SpriteSheet::new(Image::new(FRAMES.to_vec(), 3, 1).scale_factor(4), 1, 1)
+}
+
+/// Runs a sprite for each of `steps`, returning the frame shown after each one and how many
+/// times it finished
+fn play(sprite: impl Fn() -> AnimatedSprite<()> + 'static, steps: &[u64]) -> (Vec<Color>, u32) {
+    let view = move |_: &u32| -> Node<'static, (), Layout> { sprite().into() };
+    let mut app = Headless::new(
+        0,
+        |_, finished| *finished += 1,
+        view,
+        8,
+        8,
+        MAIN_LIGHT,
+        |_| None,
+    );
+    let shown = steps
+        .iter()
+        .map(|ms| {
+            app.step(&Input::default(), Duration::from_millis(*ms));
+            // the root node has 1px of padding
+            app.pixel(2, 2)
+        })
+        .collect();
+    (shown, *app.state())
+}
+
+#[test]
+fn slices_atlas_into_frames() {
+    let atlas = Image::new((0..10).map(|i| [i, 0, 0, 255]).collect(), 5, 2);
+    let sheet = SpriteSheet::new(atlas, 2, 2);
+    // the fifth column doesn't make a whole frame
+    assert_eq!(sheet.len(), 2);
+    let reds = |image: &Image<Color>| image.data.iter().map(|c| c[0]).collect::<Vec<_>>();
+    assert_eq!(reds(sheet.frame(0)), vec![0, 1, 5, 6]);
+    assert_eq!(reds(sheet.frame(1)), vec![2, 3, 7, 8]);
+    assert_eq!(sheet.frame(1).scale_factor, 1);
+}
+
+#[test]
+fn loops() {
+    let (shown, finished) = play(
+        || AnimatedSprite::new(sheet()).on_finish(|| ()),
+        &[0, 100, 50, 50, 100, 100],
+    );
+    assert_eq!(
+        shown,
+        vec![RED_DARK, BLUE_DARK, BLUE_DARK, BLUE_LIGHT, RED_DARK, BLUE_DARK]
+    );
+    assert_eq!(finished, 1);
+}
+
+#[test]
+fn long_steps_skip_frames() {
+    let (shown, _) = play(|| AnimatedSprite::new(sheet()), &[250]);
+    assert_eq!(shown, vec![BLUE_LIGHT]);
+}
+
+#[test]
+fn per_frame_durations() {
+    let sprite = || {
+        AnimatedSprite::new(sheet())
+            .frame_durations(vec![Duration::from_millis(10), Duration::from_millis(30)])
+    };
+    // the last duration carries on for the third frame
+    let (shown, _) = play(sprite, &[10, 20, 10, 20, 10]);
+    assert_eq!(
+        shown,
+        vec![BLUE_DARK, BLUE_DARK, BLUE_LIGHT, BLUE_LIGHT, RED_DARK]
+    );
+}
+
+#[test]
+fn ping_pong() {
+    let sprite = || {
+        AnimatedSprite::new(sheet())
+            .playback(Playback::PingPong)
+            .on_finish(|| ())
+    };
+    let (shown, finished) = play(sprite, &[100; 6]);
+    assert_eq!(
+        shown,
+        vec![BLUE_DARK, BLUE_LIGHT, BLUE_DARK, RED_DARK, BLUE_DARK, BLUE_LIGHT]
+    );
+    assert_eq!(finished, 1);
+}
+
+#[test]
+fn once_stops_on_the_last_frame() {
+    let sprite = || {
+        AnimatedSprite::new(sheet())
+            .playback(Playback::Once)
+            .on_finish(|| ())
+    };
+    let (shown, finished) = play(sprite, &[100, 100, 100, 100]);
+    assert_eq!(shown, vec![BLUE_DARK, BLUE_LIGHT, BLUE_LIGHT, BLUE_LIGHT]);
+    assert_eq!(finished, 1);
+}
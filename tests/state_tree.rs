@@ -0,0 +1,118 @@
+use icecube::buffer::Buffer;
+use icecube::element::Element;
+use icecube::layout::{CalculatedLayout, Layout};
+use icecube::quad::Quad;
+use icecube::slider::Slider;
+use icecube::state_tree::{self, StateNode, Tag};
+use icecube::tree::Node;
+use icecube::Input;
+
+/// Remembers the label it was created with, so tests can tell whose state is whose
+struct Labelled(&'static str);
+
+impl Element<()> for Labelled {
+    fn draw(&self, _frame: &mut Buffer, _tree: &StateNode, _region: CalculatedLayout) {}
+
+    fn get_message(
+        &mut self,
+        _tree: &mut StateNode,
+        _input: &Input,
+        _region: CalculatedLayout,
+    ) -> Option<()> {
+        None
+    }
+
+    fn tag(&self) -> Tag {
+        Tag::of::<&'static str>()
+    }
+
+    fn get_initial_state(&self) -> state_tree::State {
+        state_tree::State::new(self.0)
+    }
+}
+
+fn list(items: &[&'static str], keyed: bool) -> Node<'static, (), Layout> {
+    let mut list = Node::new(Quad::new());
+    for item in items {
+        let node = Node::new(Labelled(item));
+        list.push(if keyed { node.key(item) } else { node });
+    }
+    list
+}
+
+fn labels(state: &StateNode) -> Vec<&'static str> {
+    state
+        .children
+        .iter()
+        .map(|c| *c.state.downcast_ref::<&'static str>())
+        .collect()
+}
+
+#[test]
+fn keyed_state_follows_reordered_children() {
+    let mut state = StateNode::new(&list(&["a", "b", "c"], true));
+
+    state.diff(&list(&["c", "a", "b"], true));
+
+    assert_eq!(labels(&state), vec!["c", "a", "b"]);
+}
+
+#[test]
+fn keyed_state_survives_insertion() {
+    let mut state = StateNode::new(&list(&["a", "c"], true));
+
+    state.diff(&list(&["a", "b", "c"], true));
+
+    assert_eq!(labels(&state), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn removed_keys_drop_their_state() {
+    let mut state = StateNode::new(&list(&["a", "b", "c"], true));
+
+    state.diff(&list(&["c", "x"], true));
+
+    assert_eq!(labels(&state), vec!["c", "x"]);
+}
+
+#[test]
+fn unkeyed_children_match_by_position() {
+    let mut state = StateNode::new(&list(&["a", "b"], false));
+
+    state.diff(&list(&["c", "d", "e"], false));
+
+    // existing state is kept in place, new children get fresh state
+    assert_eq!(labels(&state), vec!["a", "b", "e"]);
+}
+
+#[test]
+fn mismatched_tag_resets_state() {
+    let mut root: Node<(), Layout> = Node::new(Quad::new());
+    root.push(Node::new(Labelled("a")));
+    let mut state = StateNode::new(&root);
+
+    let mut root: Node<(), Layout> = Node::new(Quad::new());
+    root.push(Node::new(Slider::new(0.0..1.0, 0.5)));
+    state.diff(&root);
+
+    assert_eq!(state.children[0].tag, Tag::of::<icecube::slider::State>());
+}
+
+#[test]
+fn grandchildren_are_diffed() {
+    let mut root = Node::new(Quad::new());
+    root.push(list(&["a", "b"], true));
+    let mut state = StateNode::new(&root);
+
+    let mut root = Node::new(Quad::new());
+    root.push(list(&["b", "a"], true));
+    root.push(Node::new(Labelled("new")));
+    state.diff(&root);
+
+    // the list keeps its state, and its keyed children are reordered
+    assert_eq!(labels(&state.children[0]), vec!["b", "a"]);
+    assert_eq!(
+        *state.children[1].state.downcast_ref::<&'static str>(),
+        "new"
+    );
+}
@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use icecube::headless::Headless;
+use icecube::key_binding::KeyBinding;
+use icecube::layout::{Layout, Length};
+use icecube::palette::MAIN_LIGHT;
+use icecube::text_input::TextInput;
+use icecube::tree::Node;
+use icecube::{Input, KeyCode, Modifiers};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Message {
+    Changed(String),
+    Submitted,
+}
+
+#[derive(Default, Clone)]
+struct State {
+    value: String,
+    submitted: Vec<String>,
+}
+
+fn update(m: Message, state: &mut State) {
+    match m {
+        Message::Changed(value) => state.value = value,
+        Message::Submitted => state.submitted.push(state.value.clone()),
+    }
+}
+
+fn view<'a>(state: &State) -> Node<'a, Message, Layout> {
+    Node::new(
+        TextInput::new(state.value.clone())
+            .on_change(Message::Changed)
+            .on_submit(Message::Submitted),
+    )
+    .width(Length::Grow)
+}
+
+fn frame(input: Input) -> (Input, Duration) {
+    (input, Duration::ZERO)
+}
+
+fn click(x: u32, y: u32) -> (Input, Duration) {
+    frame(Input {
+        mouse_down: true,
+        mouse_pos: Some((x, y)),
+        ..Input::default()
+    })
+}
+
+fn release() -> (Input, Duration) {
+    frame(Input {
+        mouse_released: true,
+        ..Input::default()
+    })
+}
+
+fn type_text(text: &str) -> (Input, Duration) {
+    frame(Input {
+        text: text.to_string(),
+        ..Input::default()
+    })
+}
+
+fn key(key: KeyCode, modifiers: Modifiers) -> (Input, Duration) {
+    frame(Input {
+        keys_pressed: vec![key],
+        keys_held: vec![key],
+        modifiers,
+        ..Input::default()
+    })
+}
+
+fn run<const N: usize>(script: [(Input, Duration); N]) -> State {
+    let mut app = Headless::new(State::default(), update, view, 120, 20, MAIN_LIGHT, |_| {
+        None
+    });
+    app.run(script);
+    app.state().clone()
+}
+
+#[test]
+fn typing_needs_focus() {
+    let state = run([
+        type_text("ignored"),
+        click(5, 3),
+        release(),
+        type_text("hello"),
+    ]);
+
+    assert_eq!(state.value, "hello");
+}
+
+#[test]
+fn clicking_elsewhere_loses_focus() {
+    let state = run([
+        click(5, 3),
+        release(),
+        click(5, 15),
+        release(),
+        type_text("a"),
+    ]);
+
+    assert_eq!(state.value, "");
+}
+
+#[test]
+fn editing_keys() {
+    let state = run([
+        click(5, 3),
+        release(),
+        type_text("helo"),
+        key(KeyCode::ArrowLeft, Modifiers::NONE),
+        type_text("l"),
+        key(KeyCode::End, Modifiers::NONE),
+        type_text("!"),
+        key(KeyCode::Backspace, Modifiers::NONE),
+        key(KeyCode::Home, Modifiers::NONE),
+        key(KeyCode::Delete, Modifiers::NONE),
+        type_text("H"),
+    ]);
+
+    assert_eq!(state.value, "Hello");
+}
+
+#[test]
+fn shift_selection_is_replaced_by_typing() {
+    let state = run([
+        click(5, 3),
+        release(),
+        type_text("hello world"),
+        key(KeyCode::ArrowLeft, Modifiers::SHIFT),
+        key(KeyCode::ArrowLeft, Modifiers::SHIFT),
+        key(KeyCode::ArrowLeft, Modifiers::SHIFT),
+        key(KeyCode::ArrowLeft, Modifiers::SHIFT),
+        key(KeyCode::ArrowLeft, Modifiers::SHIFT),
+        type_text("there"),
+        key(KeyCode::Home, Modifiers::SHIFT),
+        key(KeyCode::Backspace, Modifiers::NONE),
+        type_text("hi "),
+    ]);
+
+    assert_eq!(state.value, "hi ");
+}
+
+#[test]
+fn enter_submits() {
+    let state = run([
+        click(5, 3),
+        release(),
+        type_text("first"),
+        key(KeyCode::Enter, Modifiers::NONE),
+    ]);
+
+    assert_eq!(state.submitted, vec!["first".to_string()]);
+}
+
+#[test]
+fn typing_accented_characters() {
+    let state = run([
+        click(5, 3),
+        release(),
+        type_text("né"),
+        key(KeyCode::Backspace, Modifiers::NONE),
+        type_text("e"),
+    ]);
+
+    assert_eq!(state.value, "ne");
+}
+
+fn view_with_bindings<'a>(state: &State) -> Node<'a, Message, Layout> {
+    let mut bindings =
+        Node::new(KeyBinding::new().on_key(KeyCode::KeyA, Message::Submitted)).width(Length::Grow);
+    bindings.push(view(state));
+    bindings
+}
+
+#[test]
+fn focused_input_sees_keys_before_bindings() {
+    let mut app = Headless::new(
+        State::default(),
+        update,
+        view_with_bindings,
+        120,
+        20,
+        MAIN_LIGHT,
+        |_| None,
+    );
+    app.run([
+        click(5, 3),
+        release(),
+        frame(Input {
+            keys_pressed: vec![KeyCode::KeyA],
+            keys_held: vec![KeyCode::KeyA],
+            text: "a".to_string(),
+            ..Input::default()
+        }),
+    ]);
+
+    assert_eq!(app.state().value, "a");
+    assert!(app.state().submitted.is_empty());
+}
@@ -0,0 +1,127 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use icecube::font::{FontType, OLDSCHOOL};
+use icecube::headless::Headless;
+use icecube::layout::{CalculatedLayout, Layout};
+use icecube::palette::MAIN_LIGHT;
+use icecube::text::{break_lines, Text};
+use icecube::tree::Node;
+use icecube::Input;
+
+// OLDSCHOOL glyphs are 5x7 and so is a space. With a pixel between characters, each character
+// of a word takes 6, and tab stops are 20 apart.
+
+fn font() -> &'static FontType {
+    &OLDSCHOOL
+}
+
+/// The words on each line of `content` laid out `width` wide
+fn words(content: &str, width: usize) -> Vec<Vec<(Range<usize>, usize)>> {
+    break_lines(font(), content, 1, 1, width)
+        .into_iter()
+        .map(|line| line.words)
+        .collect()
+}
+
+#[test]
+fn newlines_always_break() {
+    let lines = break_lines(font(), "ab\ncd", 1, 1, 100);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].range, 0..3);
+    assert_eq!(
+        lines[0].bounds,
+        CalculatedLayout {
+            x: 0,
+            y: 0,
+            w: 12,
+            h: 7
+        }
+    );
+    assert_eq!(lines[1].range, 3..5);
+    assert_eq!(lines[1].words, vec![(3..5, 0)]);
+    assert_eq!(lines[1].bounds.y, 8);
+
+    assert_eq!(
+        words("ab\r\ncd", 100),
+        vec![vec![(0..2, 0)], vec![(4..6, 0)]]
+    );
+    // an empty line for a trailing newline, and one for no content at all
+    assert_eq!(words("ab\n", 100), vec![vec![(0..2, 0)], vec![]]);
+    assert_eq!(words("", 100), vec![vec![]]);
+}
+
+#[test]
+fn runs_of_spaces_are_kept() {
+    assert_eq!(words("a  b", 100), vec![vec![(0..1, 0), (3..4, 16)]]);
+    // including at the start of a line
+    assert_eq!(
+        words("a\n  b", 100),
+        vec![vec![(0..1, 0)], vec![(4..5, 10)]]
+    );
+}
+
+#[test]
+fn tabs_move_to_the_next_stop() {
+    assert_eq!(words("a\tb", 100), vec![vec![(0..1, 0), (2..3, 20)]]);
+    assert_eq!(words("abcd\tb", 100), vec![vec![(0..4, 0), (5..6, 40)]]);
+    assert_eq!(words("\t\tb", 100), vec![vec![(2..3, 40)]]);
+}
+
+#[test]
+fn wrapping_leaves_whitespace_on_the_line_before() {
+    let lines = break_lines(font(), "ab  cd", 1, 1, 20);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].range, 0..4);
+    assert_eq!(lines[0].bounds.w, 12);
+    assert_eq!(lines[1].range, 4..6);
+    assert_eq!(lines[1].words, vec![(4..6, 0)]);
+}
+
+#[test]
+fn text_is_as_tall_as_its_lines() {
+    let mut root: Node<(), Layout> = Node::root_node(64, 64);
+    root.push(Node::new(Text::new("a\nb\nc".into())));
+    let text = root.calculate_layout().children[0].layout;
+    assert_eq!(text.h, 3 * 8);
+
+    let boxes = Text::new("a\n\nbc".into()).line_boxes(64);
+    assert_eq!(
+        boxes.iter().map(|line| line.bounds).collect::<Vec<_>>(),
+        vec![
+            CalculatedLayout {
+                x: 0,
+                y: 0,
+                w: 6,
+                h: 7
+            },
+            CalculatedLayout {
+                x: 0,
+                y: 8,
+                w: 0,
+                h: 7
+            },
+            CalculatedLayout {
+                x: 0,
+                y: 16,
+                w: 12,
+                h: 7
+            },
+        ]
+    );
+}
+
+#[test]
+fn newlines_arent_drawn() {
+    let view = |_: &()| -> Node<'static, (), Layout> { Node::new(Text::new("a\nb".into())) };
+    let mut app = Headless::new((), |_, _| {}, view, 32, 32, MAIN_LIGHT, |_| None);
+    app.step(&Input::default(), Duration::ZERO);
+    let drawn_in = |xs: Range<u32>, ys: Range<u32>| {
+        ys.flat_map(|y| xs.clone().map(move |x| (x, y)))
+            .any(|(x, y)| app.pixel(x, y) != MAIN_LIGHT)
+    };
+    // inside the root's padding: 'a', nothing after it, then 'b' on the next line
+    assert!(drawn_in(1..6, 1..8));
+    assert!(!drawn_in(6..31, 1..8));
+    assert!(drawn_in(1..6, 9..16));
+}
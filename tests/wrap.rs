@@ -0,0 +1,145 @@
+use icecube::layout::{Align, Axis, CalculatedLayout, Justify, Layout, LayoutError, Length};
+use icecube::quad::Quad;
+use icecube::tree::Node;
+
+fn fixed(w: u32, h: u32) -> Node<'static, (), Layout> {
+    Node::new(Quad::new()).width(w).height(h)
+}
+
+fn wrap() -> Node<'static, (), Layout> {
+    Node::new(Quad::new())
+        .wrap()
+        .width(Length::Grow)
+        .height(Length::Grow)
+}
+
+/// Lays `wrap` out in a root whose inner area is 100x50 starting at (1, 1), returning where
+/// it and its children ended up
+fn layout(wrap: Node<'static, (), Layout>) -> (CalculatedLayout, Vec<CalculatedLayout>) {
+    let mut root = Node::root_node(102, 52);
+    root.push(wrap);
+    let wrap = root.calculate_layout().children.remove(0);
+    (
+        wrap.layout,
+        wrap.children.into_iter().map(|c| c.layout).collect(),
+    )
+}
+
+fn xywh(layouts: &[CalculatedLayout]) -> Vec<(u32, u32, u32, u32)> {
+    layouts.iter().map(|l| (l.x, l.y, l.w, l.h)).collect()
+}
+
+#[test]
+fn children_move_onto_new_lines() {
+    let mut wrap = wrap().spacing(2);
+    for _ in 0..3 {
+        wrap.push(fixed(40, 10));
+    }
+    let (_, children) = layout(wrap);
+    assert_eq!(
+        xywh(&children),
+        vec![(1, 1, 40, 10), (43, 1, 40, 10), (1, 13, 40, 10)]
+    );
+}
+
+#[test]
+fn children_are_aligned_within_their_line() {
+    let mut wrap = wrap().align(Align::Center);
+    wrap.push(fixed(40, 10));
+    wrap.push(fixed(40, 20));
+    wrap.push(fixed(40, 6));
+    let (_, children) = layout(wrap);
+    assert_eq!(
+        xywh(&children),
+        vec![(1, 6, 40, 10), (41, 1, 40, 20), (1, 21, 40, 6)]
+    );
+}
+
+#[test]
+fn each_line_is_justified_on_its_own() {
+    let mut wrap = wrap().justify(Justify::End);
+    for _ in 0..3 {
+        wrap.push(fixed(40, 10));
+    }
+    let (_, children) = layout(wrap);
+    assert_eq!(
+        children.iter().map(|c| c.x).collect::<Vec<_>>(),
+        vec![21, 61, 61]
+    );
+}
+
+#[test]
+fn growing_children_fill_their_line() {
+    let mut wrap = wrap();
+    wrap.push(fixed(60, 10));
+    wrap.push(Node::new(Quad::new()).width(Length::Grow).height(10));
+    wrap.push(fixed(50, 10));
+    let (_, children) = layout(wrap);
+    assert_eq!(
+        xywh(&children),
+        vec![(1, 1, 60, 10), (61, 1, 40, 10), (1, 11, 50, 10)]
+    );
+}
+
+#[test]
+fn shrinking_wrap_breaks_at_its_max_width() {
+    let mut wrap = Node::new(Quad::new()).wrap().max_width(50).spacing(1);
+    for _ in 0..3 {
+        wrap.push(fixed(20, 10));
+    }
+    let (wrap, children) = layout(wrap);
+    assert_eq!((wrap.w, wrap.h), (50, 21));
+    assert_eq!(
+        xywh(&children),
+        vec![(1, 1, 20, 10), (22, 1, 20, 10), (1, 12, 20, 10)]
+    );
+}
+
+#[test]
+fn wrapped_height_pushes_siblings_down() {
+    let mut root: Node<(), Layout> = Node::root_node(102, 52).column();
+    let mut wrap = Node::new(Quad::new()).wrap().width(Length::Grow);
+    for _ in 0..5 {
+        wrap.push(fixed(30, 10));
+    }
+    root.push(wrap);
+    root.push(fixed(10, 10));
+    let children = root.calculate_layout().children;
+    assert_eq!(children[0].layout.h, 20);
+    assert_eq!(children[1].layout.y, 21);
+}
+
+#[test]
+fn only_a_child_wider_than_the_line_overflows() {
+    let mut fits = wrap();
+    for _ in 0..4 {
+        fits.push(fixed(40, 10));
+    }
+    let mut root = Node::root_node(102, 52);
+    root.push(fits);
+    assert!(root.try_calculate_layout().is_ok());
+
+    let mut too_wide = wrap().name("tags");
+    too_wide.push(fixed(40, 10));
+    too_wide.push(fixed(120, 10));
+    too_wide.push(fixed(40, 10));
+    let mut root = Node::root_node(102, 52);
+    root.push(too_wide);
+    let Err(LayoutError::Overflow { node, axis, .. }) = root.try_calculate_layout() else {
+        panic!("expected an overflow");
+    };
+    assert_eq!((node.as_deref(), axis), (Some("tags"), Axis::Horizontal));
+}
+
+#[test]
+fn too_wide_child_gets_a_line_of_its_own() {
+    let mut wrap = wrap();
+    wrap.push(fixed(40, 10));
+    wrap.push(fixed(120, 10));
+    wrap.push(fixed(40, 10));
+    let (_, children) = layout(wrap);
+    assert_eq!(
+        children.iter().map(|c| (c.x, c.y)).collect::<Vec<_>>(),
+        vec![(1, 1), (1, 11), (1, 21)]
+    );
+}